@@ -1,9 +1,9 @@
 use trikz::anchor::AnchorT;
-use trikz::elements::Document;
-use trikz::style::{Color, Stroke, Style};
+use trikz::elements::{Document, PathBuilder, Shape};
+use trikz::style::{Color, Font, Stroke, Style};
 use trikz::*; // TODO should be prelude (AnchorT as well)
 
-fn main() {
+fn main() -> Result<(), Error> {
     let width = cm!(2);
     let height = cm!(1.25);
 
@@ -12,27 +12,35 @@ fn main() {
     {
         let arrow_marker = document.marker().arrow();
 
-        let stroke = Stroke::new().color(Color::Black).width(mm!(0.5));
+        let _stroke = Stroke::new().color(Color::Black).width(mm!(0.5));
         let arrow = Stroke::new()
             .color(Color::Black)
             .width(mm!(1.0))
-            .marker_end(arrow_marker.id());
+            .marker_end(arrow_marker.id().unwrap());
 
-        let rect_style = Style::new().fill(Color::Green);//.stroke(stroke.clone());
-        let circ_style = Style::new().fill(Color::Blue);//.stroke(stroke.clone());
+        let rect_style = Style::new().fill(Color::Green);//.stroke(_stroke.clone());
+        let circ_style = Style::new().fill(Color::Blue);//.stroke(_stroke.clone());
         let arrow_style = Style::new().stroke(arrow);
+        let label_style = Style::new().fill(Color::Black).font(Font::new().s());
 
         let controller = document
-            .rectangle()
-            .width(width)
-            .height(height)
+            .node(Shape::Rectangle)
+            .text("controller")
+            .minimum_width(width)
+            .minimum_height(height)
             .rounded_corners(px!(5))
-            .with_style(&rect_style);
+            .with_style(&rect_style)
+            .with_font(&label_style);
 
         let plant = document
-            .rectangle()
-            .like(controller.clone())
-            .at(controller.right(2.0 * width));
+            .node(Shape::Rectangle)
+            .text("plant")
+            .minimum_width(width)
+            .minimum_height(height)
+            .rounded_corners(px!(5))
+            .at(controller.right(2.0 * width))
+            .with_style(&rect_style)
+            .with_font(&label_style);
 
         let sum = document
             .circle()
@@ -65,11 +73,20 @@ fn main() {
             .end(plant.right(2.0 * width))
             .with_style(&arrow_style);
 
-        //let _a_fdb = document.path(
+        let _a_fdb = document
+            .path(
+                PathBuilder::start(plant.right(width))
+                    .vline_to(plant.below(height)[1])
+                    .hv_to(sum.south())
+                    .rounded_corners(px!(5))
+                    .end(),
+            )
+            .with_style(&arrow_style);
     }
 
     #[cfg(not(feature = "pdf"))]
-    document.save("examples/blockdiag.svg");
+    document.save("examples/blockdiag.svg")?;
     #[cfg(feature = "pdf")]
-    document.save_pdf("examples/blockdiag.pdf");
+    document.save_pdf("examples/blockdiag.pdf")?;
+    Ok(())
 }
@@ -1,7 +1,9 @@
+use crate::transform::Transform;
 use crate::{Scalar, Vector2};
 
 const DEFAULT_RADIUS: Scalar = 5.0;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Anchor {
     Origin,
     North,
@@ -12,7 +14,14 @@ pub enum Anchor {
     SouthWest,
     West,
     NorthWest,
-    Polar { radius: Scalar, angle: Scalar },
+    /// Point at `radius` from the origin in direction `angle` (degrees), on every shape. Points
+    /// relative to the outline are given by `Border` instead.
+    Polar {
+        radius: Scalar,
+        angle: Scalar,
+    },
+    /// Point where the ray from the origin in direction `angle` (degrees) leaves the shape.
+    Border(Scalar),
 }
 
 // positive X is right (east)
@@ -70,6 +79,9 @@ pub trait AnchorT {
     fn northwest(&self) -> Vector2 {
         self.anchor(Anchor::NorthWest)
     }
+    fn border(&self, angle: Scalar) -> Vector2 {
+        self.anchor(Anchor::Border(angle))
+    }
 }
 
 impl AnchorT for Vector2 {
@@ -78,12 +90,41 @@ impl AnchorT for Vector2 {
     }
 }
 
-fn polar_coordinates(radius: Scalar, angle: Scalar) -> Vector2 {
+pub(crate) fn polar_coordinates(radius: Scalar, angle: Scalar) -> Vector2 {
     let radians = angle * crate::PI / 180.0;
     let (s, c) = radians.sin_cos();
     Vector2::new(radius * c, radius * s)
 }
 
+/// Anchor of a shape drawn with `transform`, where `local` gives the anchors of the shape without
+/// it. Compass anchors move with the shape, while the angles of polar and border anchors are kept
+/// in the coordinate system of the document.
+pub fn anchor_transformed<F: Fn(Anchor) -> Vector2>(
+    anchor: Anchor,
+    transform: &Transform,
+    local: F,
+) -> Vector2 {
+    if transform.is_identity() {
+        return local(anchor);
+    }
+    let local_angle = |angle: Scalar| match transform.inverse() {
+        Some(inverse) => {
+            let direction = inverse.apply_vector(polar_coordinates(1.0, angle));
+            direction[1].atan2(direction[0]).to_degrees()
+        }
+        None => angle,
+    };
+    let anchor = match anchor {
+        Anchor::Polar { radius, angle } => Anchor::Polar {
+            radius,
+            angle: local_angle(angle),
+        },
+        Anchor::Border(angle) => Anchor::Border(local_angle(angle)),
+        anchor => anchor,
+    };
+    transform.apply(local(anchor))
+}
+
 pub fn anchor_circle(anchor: Anchor, origin: Vector2, radius: Scalar) -> Vector2 {
     // positive X is right (east)
     // positive Y is up (north)
@@ -98,6 +139,7 @@ pub fn anchor_circle(anchor: Anchor, origin: Vector2, radius: Scalar) -> Vector2
         Anchor::SouthWest => (radius, -135.0),
         Anchor::NorthWest => (radius, 135.0),
         Anchor::Polar { radius, angle } => (radius, angle),
+        Anchor::Border(angle) => (radius, angle),
     };
 
     origin + polar_coordinates(radius, angle)
@@ -108,6 +150,17 @@ pub fn anchor_rectangle(
     origin: Vector2,
     half_width: Scalar,
     half_height: Scalar,
+) -> Vector2 {
+    anchor_rounded_rectangle(anchor, origin, half_width, half_height, 0.0)
+}
+
+/// Like [`anchor_rectangle`], but border anchors follow the rounded corners.
+pub fn anchor_rounded_rectangle(
+    anchor: Anchor,
+    origin: Vector2,
+    half_width: Scalar,
+    half_height: Scalar,
+    corner_radius: Scalar,
 ) -> Vector2 {
     // positive X is right (east)
     // positive Y is up (north)
@@ -122,10 +175,119 @@ pub fn anchor_rectangle(
         Anchor::West => -half_width * Vector2::x(),
         Anchor::NorthWest => Vector2::new(-half_width, half_height),
         Anchor::Polar { radius, angle } => polar_coordinates(radius, angle),
+        Anchor::Border(angle) => {
+            let direction = polar_coordinates(1.0, angle);
+            let half = Vector2::new(half_width, half_height);
+            direction * rounded_rectangle_distance(direction, half, corner_radius)
+        }
     };
     origin + shift
 }
 
+// distance from the center to the border of a rectangle with rounded corners along the direction
+fn rounded_rectangle_distance(direction: Vector2, half: Vector2, corner_radius: Scalar) -> Scalar {
+    let (dx, dy) = (direction[0].abs(), direction[1].abs());
+    let distance = (half[0] / dx).min(half[1] / dy);
+    let radius = corner_radius.clamp(0.0, half[0].min(half[1]));
+    let point = distance * Vector2::new(dx, dy);
+    // the ray hits a corner, which is cut off by a quarter circle
+    let center = half - Vector2::repeat(radius);
+    if radius == 0.0 || point[0] <= center[0] || point[1] <= center[1] {
+        return distance;
+    }
+    // farther solution of |t * direction - center| = radius
+    let direction = Vector2::new(dx, dy);
+    let projection = direction.dot(&center);
+    let discriminant = projection.powi(2) - center.norm_squared() + radius.powi(2);
+    projection + discriminant.max(0.0).sqrt()
+}
+
+fn anchor_border<F>(anchor: Anchor, origin: Vector2, distance: F) -> Vector2
+where
+    F: Fn(Vector2) -> Scalar,
+{
+    let angle = match anchor {
+        Anchor::Origin => return origin,
+        Anchor::North => 90.0,
+        Anchor::East => 0.0,
+        Anchor::South => -90.0,
+        Anchor::West => 180.0,
+        Anchor::NorthEast => 45.0,
+        Anchor::SouthEast => -45.0,
+        Anchor::SouthWest => -135.0,
+        Anchor::NorthWest => 135.0,
+        Anchor::Polar { radius, angle } => return origin + polar_coordinates(radius, angle),
+        Anchor::Border(angle) => angle,
+    };
+    let direction = polar_coordinates(1.0, angle);
+    origin + distance(direction) * direction
+}
+
+pub fn anchor_ellipse(
+    anchor: Anchor,
+    origin: Vector2,
+    radius_x: Scalar,
+    radius_y: Scalar,
+) -> Vector2 {
+    anchor_border(anchor, origin, |direction| {
+        1.0 / ((direction[0] / radius_x).powi(2) + (direction[1] / radius_y).powi(2)).sqrt()
+    })
+}
+
+pub fn anchor_diamond(
+    anchor: Anchor,
+    origin: Vector2,
+    half_width: Scalar,
+    half_height: Scalar,
+) -> Vector2 {
+    anchor_border(anchor, origin, |direction| {
+        1.0 / (direction[0].abs() / half_width + direction[1].abs() / half_height)
+    })
+}
+
+/// Anchors on the closed outline through the given points, found by casting a ray from the
+/// centroid. Where the ray crosses the outline more than once, the farthest crossing is used.
+pub fn anchor_polygon(anchor: Anchor, points: &[Vector2]) -> Vector2 {
+    let origin = centroid(points);
+    anchor_border(anchor, origin, |direction| {
+        let edges = points.iter().zip(points.iter().cycle().skip(1));
+        edges
+            .filter_map(|(a, b)| ray_intersection(origin, direction, *a, *b))
+            .fold(0.0, Scalar::max)
+    })
+}
+
+/// Area centroid of the polygon, or the average of the points if it has no area.
+pub(crate) fn centroid(points: &[Vector2]) -> Vector2 {
+    let edges = points.iter().zip(points.iter().cycle().skip(1));
+    let (area, weighted) = edges.fold((0.0, Vector2::zeros()), |(area, weighted), (a, b)| {
+        let cross = a[0] * b[1] - b[0] * a[1];
+        (area + cross, weighted + cross * (a + b))
+    });
+    if area.abs() <= Scalar::EPSILON {
+        points.iter().sum::<Vector2>() / points.len().max(1) as Scalar
+    } else {
+        weighted / (3.0 * area)
+    }
+}
+
+// distance along the ray to the segment between a and b, if they meet
+fn ray_intersection(origin: Vector2, direction: Vector2, a: Vector2, b: Vector2) -> Option<Scalar> {
+    let edge = b - a;
+    let offset = a - origin;
+    let tolerance = 1e-4;
+    let denominator = direction.perp(&edge);
+    if denominator.abs() <= tolerance * edge.norm() {
+        // the ray may run along the edge, then its far end is hit
+        let collinear = offset.perp(&direction).abs() <= tolerance * offset.norm().max(1.0);
+        let distance = offset.dot(&direction).max((b - origin).dot(&direction));
+        return (collinear && distance >= 0.0).then_some(distance);
+    }
+    let distance = offset.perp(&edge) / denominator;
+    let along_edge = offset.perp(&direction) / denominator;
+    ((-tolerance..=1.0 + tolerance).contains(&along_edge) && distance >= 0.0).then_some(distance)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -239,6 +401,131 @@ mod test {
         );
     }
 
+    #[test]
+    fn ellipse() {
+        let origin = Vector2::new(1.0, 2.0);
+        let (rx, ry) = (4.0, 2.0);
+        assert_relative_eq!(anchor_ellipse(Anchor::Origin, origin, rx, ry), origin);
+        assert_relative_eq!(
+            anchor_ellipse(Anchor::North, origin, rx, ry),
+            origin + Vector2::new(0.0, ry)
+        );
+        assert_relative_eq!(
+            anchor_ellipse(Anchor::West, origin, rx, ry),
+            origin - Vector2::new(rx, 0.0)
+        );
+        // the diagonal anchors have to be on the border
+        let ne = anchor_ellipse(Anchor::NorthEast, origin, rx, ry) - origin;
+        assert!(((ne[0] / rx).powi(2) + (ne[1] / ry).powi(2) - 1.0).abs() < 1e-5);
+        assert!((ne[0] - ne[1]).abs() < 1e-5);
+        // polar radius is measured from the center, the border has its own anchor
+        let anchor = Anchor::Polar {
+            radius: 1.0,
+            angle: -90.0,
+        };
+        assert_relative_eq!(
+            anchor_ellipse(anchor, origin, rx, ry),
+            origin - Vector2::new(0.0, 1.0)
+        );
+        assert_relative_eq!(
+            anchor_ellipse(Anchor::Border(-90.0), origin, rx, ry),
+            origin - Vector2::new(0.0, ry)
+        );
+    }
+
+    #[test]
+    fn diamond() {
+        let origin = Vector2::zeros();
+        let (a, b) = (6.0, 3.0);
+        assert_relative_eq!(
+            anchor_diamond(Anchor::East, origin, a, b),
+            Vector2::new(a, 0.0)
+        );
+        assert_relative_eq!(
+            anchor_diamond(Anchor::South, origin, a, b),
+            Vector2::new(0.0, -b)
+        );
+        // |x| / a + |y| / b = 1 on the border, with x = y along the diagonal
+        let sw = anchor_diamond(Anchor::SouthWest, origin, a, b);
+        assert!((sw - Vector2::new(-2.0, -2.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn border() {
+        let origin = Vector2::new(1.0, 1.0);
+        assert_relative_eq!(
+            anchor_circle(Anchor::Border(90.0), origin, 2.0),
+            Vector2::new(1.0, 3.0)
+        );
+        assert!(
+            (anchor_ellipse(Anchor::Border(0.0), origin, 3.0, 1.0) - Vector2::new(4.0, 1.0)).norm()
+                < 1e-5
+        );
+
+        // the ray at 30 degrees leaves a wide rectangle through its right edge
+        let point = anchor_rectangle(Anchor::Border(30.0), Vector2::zeros(), 4.0, 3.0);
+        assert!((point - Vector2::new(4.0, 4.0 * 30.0_f32.to_radians().tan())).norm() < 1e-5);
+        // and at 60 degrees through its top edge
+        let point = anchor_rectangle(Anchor::Border(60.0), Vector2::zeros(), 4.0, 3.0);
+        assert!((point[1] - 3.0).abs() < 1e-5);
+        assert!((point[0] - 3.0 / 60.0_f32.to_radians().tan()).abs() < 1e-5);
+
+        // rounded corners move the diagonal anchor onto the arc
+        let point = anchor_rounded_rectangle(Anchor::Border(45.0), Vector2::zeros(), 2.0, 2.0, 1.0);
+        let corner_center = Vector2::new(1.0, 1.0);
+        assert!(((point - corner_center).norm() - 1.0).abs() < 1e-5);
+        assert!((point[0] - point[1]).abs() < 1e-5);
+        // while the straight parts of the edges are not affected
+        let point = anchor_rounded_rectangle(Anchor::Border(10.0), Vector2::zeros(), 2.0, 2.0, 1.0);
+        assert!((point[0] - 2.0).abs() < 1e-5);
+        // compass anchors stay on the bounding box
+        assert_relative_eq!(
+            anchor_rounded_rectangle(Anchor::NorthEast, Vector2::zeros(), 2.0, 2.0, 1.0),
+            Vector2::new(2.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn polygon() {
+        // triangle with its centroid at the origin
+        let triangle = [
+            Vector2::new(-3.0, -1.0),
+            Vector2::new(3.0, -1.0),
+            Vector2::new(0.0, 2.0),
+        ];
+        assert_relative_eq!(centroid(&triangle), Vector2::zeros());
+        assert!((anchor_polygon(Anchor::North, &triangle) - Vector2::new(0.0, 2.0)).norm() < 1e-5);
+        assert!((anchor_polygon(Anchor::South, &triangle) - Vector2::new(0.0, -1.0)).norm() < 1e-5);
+        assert!((anchor_polygon(Anchor::East, &triangle) - Vector2::new(2.0, 0.0)).norm() < 1e-5);
+        let polar = anchor_polygon(
+            Anchor::Polar {
+                radius: 2.0,
+                angle: -90.0,
+            },
+            &triangle,
+        );
+        assert!((polar - Vector2::new(0.0, -2.0)).norm() < 1e-5);
+        let border = anchor_polygon(Anchor::Border(-90.0), &triangle);
+        assert!((border - Vector2::new(0.0, -1.0)).norm() < 1e-5);
+
+        // the farthest crossing is used for concave outlines
+        let notched = [
+            Vector2::new(-2.0, -2.0),
+            Vector2::new(2.0, -2.0),
+            Vector2::new(2.0, 2.0),
+            Vector2::new(0.0, 0.5),
+            Vector2::new(-2.0, 2.0),
+        ];
+        let origin = centroid(&notched);
+        let east = anchor_polygon(Anchor::East, &notched);
+        assert!((east - Vector2::new(2.0, origin[1])).norm() < 1e-5);
+
+        // points on a line have no area
+        let line = [Vector2::zeros(), Vector2::new(4.0, 0.0)];
+        assert_relative_eq!(centroid(&line), Vector2::new(2.0, 0.0));
+        assert!((anchor_polygon(Anchor::West, &line) - Vector2::zeros()).norm() < 1e-5);
+    }
+
     #[test]
     fn coordinate() {
         let coordinate = Vector2::zeros();
@@ -0,0 +1,144 @@
+use crate::anchor::{anchor_rectangle, Anchor, AnchorT};
+use crate::transform::Transform;
+use crate::{Scalar, Vector2};
+use nalgebra::Matrix2;
+
+/// Axis-aligned rectangle given by its lower left and upper right corners.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min: Vector2,
+    pub max: Vector2,
+}
+
+impl BoundingBox {
+    pub fn new(a: Vector2, b: Vector2) -> Self {
+        Self {
+            min: a.inf(&b),
+            max: a.sup(&b),
+        }
+    }
+
+    pub fn from_points<I: IntoIterator<Item = Vector2>>(points: I) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|point| Self::new(point, point))
+            .reduce(|acc, x| acc.union(&x))
+    }
+
+    /// Tight box around the image of the unit circle under `shape`, centered at `center`.
+    pub fn ellipse(center: Vector2, shape: &Matrix2<Scalar>) -> Self {
+        let half = Vector2::new(
+            shape[(0, 0)].hypot(shape[(0, 1)]),
+            shape[(1, 0)].hypot(shape[(1, 1)]),
+        );
+        Self::new(center - half, center + half)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    /// Overlap of the two boxes, `None` if they are disjoint.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = self.min.sup(&other.min);
+        let max = self.max.inf(&other.max);
+        (min[0] <= max[0] && min[1] <= max[1]).then_some(Self { min, max })
+    }
+
+    /// Grows the box by `margin` in every direction.
+    pub fn expand(&self, margin: Scalar) -> Self {
+        Self::new(
+            self.min - Vector2::repeat(margin),
+            self.max + Vector2::repeat(margin),
+        )
+    }
+
+    /// Smallest box around the transformed corners of this one.
+    pub fn transform(&self, transform: &Transform) -> Self {
+        let corners = [
+            self.min,
+            Vector2::new(self.max[0], self.min[1]),
+            self.max,
+            Vector2::new(self.min[0], self.max[1]),
+        ];
+        // NOTE there are always four corners
+        Self::from_points(corners.map(|corner| transform.apply(corner))).unwrap()
+    }
+
+    pub fn size(&self) -> Vector2 {
+        self.max - self.min
+    }
+
+    pub fn width(&self) -> Scalar {
+        self.max[0] - self.min[0]
+    }
+
+    pub fn height(&self) -> Scalar {
+        self.max[1] - self.min[1]
+    }
+
+    pub fn center(&self) -> Vector2 {
+        (self.min + self.max) / 2.0
+    }
+}
+
+/// Geometric extent of an element, without its stroke and markers.
+pub trait Bounded {
+    fn bounding_box(&self) -> BoundingBox;
+}
+
+impl AnchorT for BoundingBox {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        let half = self.size() / 2.0;
+        anchor_rectangle(anchor, self.center(), half[0], half[1])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let bbox = BoundingBox::new(Vector2::new(3.0, -1.0), Vector2::new(-2.0, 4.0));
+        assert_eq!(bbox.min, Vector2::new(-2.0, -1.0));
+        assert_eq!(bbox.max, Vector2::new(3.0, 4.0));
+        assert_eq!(bbox.width(), 5.0);
+        assert_eq!(bbox.height(), 5.0);
+        assert_eq!(bbox.center(), Vector2::new(0.5, 1.5));
+        assert_eq!(bbox.northeast(), bbox.max);
+        assert_eq!(bbox.southwest(), bbox.min);
+
+        let bbox = bbox.expand(1.0);
+        assert_eq!(bbox.min, Vector2::new(-3.0, -2.0));
+        assert_eq!(bbox.size(), Vector2::new(7.0, 7.0));
+
+        assert!(BoundingBox::from_points([]).is_none());
+        let bbox = BoundingBox::from_points([
+            Vector2::new(1.0, 1.0),
+            Vector2::new(-1.0, 2.0),
+            Vector2::new(0.0, -5.0),
+        ])
+        .unwrap();
+        assert_eq!(bbox.min, Vector2::new(-1.0, -5.0));
+        assert_eq!(bbox.max, Vector2::new(1.0, 2.0));
+
+        let other = BoundingBox::new(Vector2::zeros(), Vector2::new(10.0, 1.0));
+        let union = bbox.union(&other);
+        assert_eq!(union.min, Vector2::new(-1.0, -5.0));
+        assert_eq!(union.max, Vector2::new(10.0, 2.0));
+        assert_eq!(
+            bbox.intersection(&other),
+            Some(BoundingBox::new(Vector2::zeros(), Vector2::new(1.0, 1.0)))
+        );
+        let apart = BoundingBox::new(Vector2::new(2.0, 0.0), Vector2::new(3.0, 1.0));
+        assert!(bbox.intersection(&apart).is_none());
+
+        let rotated = other.transform(&Transform::rotation(90.0, Vector2::zeros()));
+        assert!((rotated.min - Vector2::new(-1.0, 0.0)).norm() < 1e-5);
+        assert!((rotated.max - Vector2::new(0.0, 10.0)).norm() < 1e-5);
+    }
+}
@@ -1,5 +1,6 @@
 use super::{Element, ReprT};
-use crate::anchor::{anchor_circle, Anchor, AnchorT};
+use crate::anchor::{anchor_circle, anchor_transformed, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
 use crate::svgutils::keys;
 use crate::{Scalar, Vector2};
 
@@ -37,10 +38,21 @@ impl Element<Circle> {
     }
 }
 
+impl Bounded for Element<Circle> {
+    fn bounding_box(&self) -> BoundingBox {
+        let geometry = self.geometry();
+        let transform = self.transformation();
+        let shape = transform.linear() * geometry.radius;
+        BoundingBox::ellipse(transform.apply(geometry.origin), &shape)
+    }
+}
+
 impl AnchorT for Element<Circle> {
     fn anchor(&self, anchor: Anchor) -> Vector2 {
         let geometry = self.geometry();
-        anchor_circle(anchor, geometry.origin, geometry.radius)
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            anchor_circle(anchor, geometry.origin, geometry.radius)
+        })
     }
 }
 
@@ -69,5 +81,17 @@ mod test {
         let geometry = other_circ.geometry();
         assert_eq!(geometry.origin, Vector2::new(12.0, -32.5));
         assert_eq!(geometry.radius, 5.0);
+
+        let bbox = other_circ.bounding_box();
+        assert_eq!(bbox.min, Vector2::new(7.0, -37.5));
+        assert_eq!(bbox.max, Vector2::new(17.0, -27.5));
+
+        // the box stays tight around the turned circle instead of around its turned box
+        let rotated = other_circ
+            .radius(1.0)
+            .rotate(45.0, Vector2::new(12.0, -32.5));
+        let bbox = rotated.bounding_box();
+        assert!((bbox.min - Vector2::new(11.0, -33.5)).norm() < 1e-5);
+        assert!((bbox.max - Vector2::new(13.0, -31.5)).norm() < 1e-5);
     }
 }
@@ -1,8 +1,36 @@
 use super::*;
+use crate::anchor::AnchorT;
+use crate::bounding_box::BoundingBox;
+use crate::svgutils::keys;
+use crate::{Scalar, Vector2};
+use raw::Node as _;
+use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
+
+const DEFAULT_BORDER: Scalar = 5.0;
+// width of strokes that do not set one, as in SVG
+const DEFAULT_STROKE_WIDTH: Scalar = 1.0;
 
-#[derive(Default)]
 pub struct Document {
-    elements: Vec<ElemRef>,
+    elements: Vec<Item>,
+    markers: Vec<ElemRef>,
+    symbols: Vec<Symbol>,
+    border: Scalar,
+    crop: Option<BoundingBox>,
+    canvas: Option<Vector2>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self {
+            elements: Vec::new(),
+            markers: Vec::new(),
+            symbols: Vec::new(),
+            border: DEFAULT_BORDER,
+            crop: None,
+            canvas: None,
+        }
+    }
 }
 
 impl Document {
@@ -10,50 +38,946 @@ impl Document {
         Self::default()
     }
 
+    /// Space left around the elements when the view box is computed automatically.
+    pub fn border(&mut self, border: Scalar) -> &mut Self {
+        self.border = border;
+        self
+    }
+
+    /// Shows only the given region instead of the bounding box of all elements.
+    pub fn crop(&mut self, region: BoundingBox) -> &mut Self {
+        self.crop = Some(region);
+        self
+    }
+
+    /// Sets the width and height of the output, the view box is scaled to fit.
+    pub fn canvas(&mut self, width: Scalar, height: Scalar) -> &mut Self {
+        self.canvas = Some(Vector2::new(width, height));
+        self
+    }
+
     fn add<T, E: Into<raw::Element>>(&mut self, elem: E) -> Element<T> {
-        self.elements.push(Rc::new(RefCell::new(elem.into())));
-        let index = self.elements.len() - 1;
-        Element::new(Rc::clone(&self.elements[index]))
+        let elem = Rc::new(RefCell::new(elem.into()));
+        self.elements.push(Item::Element(Rc::clone(&elem)));
+        Element::new(elem)
+    }
+
+    /// The elements created by `build` are drawn in a group of their own, see `Scope`. Scopes
+    /// can be nested.
+    pub fn scope<F: FnOnce(&mut Self)>(&mut self, build: F) -> Scope {
+        let outer = std::mem::take(&mut self.elements);
+        build(self);
+        let items = std::mem::replace(&mut self.elements, outer);
+        let group = Rc::new(RefCell::new(raw::Group::new().into()));
+        let scope = Scope::new(Element::new(group), items);
+        self.elements.push(Item::Scope(scope.clone()));
+        scope
+    }
+
+    /// The elements created by `build` are not drawn directly but make up a symbol, which is
+    /// defined once and drawn by each of its instances.
+    pub fn symbol<F: FnOnce(&mut Self)>(&mut self, build: F) -> Symbol {
+        let outer = std::mem::take(&mut self.elements);
+        build(self);
+        let items = std::mem::replace(&mut self.elements, outer);
+        let symbol = Symbol::new(self.symbols.len(), items);
+        self.symbols.push(symbol.clone());
+        symbol
+    }
+
+    pub fn instance(&mut self, symbol: &Symbol) -> Instance {
+        let reference = raw::Use::new().set(keys::HREF, format!("#{}", symbol.id()));
+        let instance = Instance::new(
+            Element::new(Rc::new(RefCell::new(reference.into()))),
+            symbol.clone(),
+        );
+        self.elements.push(Item::Instance(instance.clone()));
+        instance
     }
 
     pub fn circle(&mut self) -> Element<Circle> {
         self.add(raw::Circle::new())
     }
 
+    pub fn ellipse(&mut self) -> Element<Ellipse> {
+        self.add(raw::Ellipse::new())
+    }
+
     pub fn line(&mut self) -> Element<Line> {
         self.add(raw::Line::new())
     }
 
-    //pub fn path(&mut self) -> Element<Path> {
-    //    self.add(raw::Path::new())
-    //}
+    /// Line between the borders of two elements, on the line between their origins.
+    pub fn connect<A: AnchorT, B: AnchorT>(&mut self, from: &A, to: &B) -> Element<Line> {
+        let delta = to.origin() - from.origin();
+        let angle = delta[1].atan2(delta[0]).to_degrees();
+        self.line()
+            .start(from.border(angle))
+            .end(to.border(angle + 180.0))
+    }
+
+    /// Right-angled path between the borders of two elements, see `PathBuilder::route`.
+    pub fn route<A: AnchorT, B: AnchorT>(
+        &mut self,
+        from: &A,
+        to: &B,
+        routing: Routing,
+    ) -> Element<Path> {
+        self.path(PathBuilder::route(from, to, routing).end())
+    }
+
+    /// Paths are not filled unless a fill is set explicitly through their style.
+    pub fn path(&mut self, path: Path) -> Element<Path> {
+        self.add(path.into_raw().set(keys::FILL, "none"))
+    }
 
+    /// Markers get a unique id and end up in the definitions of the document.
     pub fn marker(&mut self) -> Element<Marker> {
-        self.add(raw::Marker::new())
+        let index = self.markers.len();
+        let marker = raw::Marker::new().set(keys::MARKER_ID, MarkerId::new(index).to_string());
+        self.markers.push(Rc::new(RefCell::new(marker.into())));
+        Element::new(Rc::clone(&self.markers[index]))
+    }
+
+    pub fn polygon(&mut self, points: &[Vector2]) -> Element<Polygon> {
+        self.add::<Polygon, _>(raw::Polygon::new()).points(points)
+    }
+
+    /// Polylines are not filled unless a fill is set explicitly through their style.
+    pub fn polyline(&mut self, points: &[Vector2]) -> Element<Polyline> {
+        self.add::<Polyline, _>(raw::Polyline::new().set(keys::FILL, "none"))
+            .points(points)
+    }
+
+    /// Like in TikZ, odd polygons have a corner at the top and even ones a flat top edge.
+    pub fn regular_polygon(
+        &mut self,
+        center: Vector2,
+        radius: Scalar,
+        sides: usize,
+    ) -> Element<Polygon> {
+        self.polygon(&polygon::regular_polygon(center, radius, sides))
+    }
+
+    pub fn triangle(&mut self, center: Vector2, radius: Scalar) -> Element<Polygon> {
+        self.regular_polygon(center, radius, 3)
+    }
+
+    pub fn star(
+        &mut self,
+        center: Vector2,
+        outer_radius: Scalar,
+        inner_radius: Scalar,
+        tips: usize,
+    ) -> Element<Polygon> {
+        self.polygon(&polygon::star(center, outer_radius, inner_radius, tips))
+    }
+
+    /// Decision shape of flowcharts.
+    pub fn diamond(&mut self, center: Vector2, width: Scalar, height: Scalar) -> Element<Polygon> {
+        self.polygon(&polygon::diamond(center, width, height))
     }
 
     pub fn rectangle(&mut self) -> Element<Rectangle> {
         self.add(raw::Rectangle::new())
     }
 
-    pub fn finalize(self) -> raw::Document {
-        let mut document = raw::Document::new().set("viewBox", "-50 -50 100 100");
-        self.elements.into_iter().for_each(|elem| {
-            document.append(Rc::into_inner(elem).unwrap().into_inner());
-        });
-        document
+    pub fn text(&mut self) -> Element<Text> {
+        self.add(raw::Text::new().set(keys::TRANSFORM, FLIP_Y))
+    }
+
+    pub fn node(&mut self, shape: Shape) -> Node {
+        let outline = self.add(shape.raw());
+        let label = self.text();
+        Node::new(shape, outline, label)
+    }
+
+    /// Union of the bounding boxes of all elements, including their stroke and markers.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let inherited = raw::Attributes::new();
+        self.elements
+            .iter()
+            .filter_map(|item| self.item_bounding_box(item, &inherited))
+            .reduce(|acc, x| acc.union(&x))
+    }
+
+    /// Bounding box of an element including its stroke and markers, like the stroke bounding
+    /// box of SVG. `Bounded::bounding_box` gives the geometry alone. Only the style of the
+    /// element itself is taken into account, not the one it inherits from a scope.
+    pub fn stroke_bounding_box<T>(&self, element: &Element<T>) -> Option<BoundingBox> {
+        self.element_bounding_box(&element.elem, &raw::Attributes::new())
+    }
+
+    fn item_bounding_box(&self, item: &Item, inherited: &raw::Attributes) -> Option<BoundingBox> {
+        match item {
+            Item::Element(elem) => self.element_bounding_box(elem, inherited),
+            Item::Scope(scope) => {
+                let inherited = passed_on(scope, inherited);
+                scope.enclose(|item| self.item_bounding_box(item, &inherited))
+            }
+            Item::Instance(instance) => {
+                instance.enclose(|item| self.item_bounding_box(item, inherited))
+            }
+        }
+    }
+
+    fn element_bounding_box(
+        &self,
+        elem: &ElemRef,
+        inherited: &raw::Attributes,
+    ) -> Option<BoundingBox> {
+        let bbox = geometry(elem)?;
+        let element = inherit(elem, inherited);
+        let stroke_width = element.get_or(keys::STROKE_WIDTH, DEFAULT_STROKE_WIDTH);
+        let marker_size = keys::MARKERS
+            .iter()
+            .filter_map(|key| self.marker_size(&marker_reference(&element, key)?))
+            .fold(0.0, Scalar::max);
+        // markers scale with the stroke width by default, and are drawn even without a stroke
+        let stroke: String = element.get(keys::STROKE);
+        let half_stroke = if stroke.is_empty() || stroke == "none" {
+            0.0
+        } else {
+            0.5
+        };
+        Some(bbox.expand(stroke_width * (half_stroke + marker_size)))
+    }
+
+    fn marker_size(&self, id: &str) -> Option<Scalar> {
+        self.markers
+            .iter()
+            .map(|elem| Element::<Marker>::new(Rc::clone(elem)))
+            .find(|marker| marker.get::<String>(keys::MARKER_ID) == id)
+            .map(|marker| {
+                let width: Scalar = marker.get(keys::MARKER_WIDTH);
+                let height: Scalar = marker.get(keys::MARKER_HEIGHT);
+                width.max(height)
+            })
+    }
+
+    fn view_box(&self) -> BoundingBox {
+        self.crop.unwrap_or_else(|| {
+            self.bounding_box()
+                .unwrap_or(BoundingBox::new(Vector2::zeros(), Vector2::zeros()))
+                .expand(self.border)
+        })
+    }
+
+    /// Builds the SVG document. Handles of elements may still be alive, the elements are copied.
+    pub fn finalize(self) -> Result<raw::Document, Error> {
+        let view_box = self.view_box();
+        let size = self.canvas.unwrap_or(view_box.size());
+        // the view box is given in SVG coordinates, where the top edge has the lowest Y
+        let mut document = raw::Document::new()
+            .set(
+                keys::VIEW_BOX,
+                (
+                    view_box.min[0],
+                    -view_box.max[1],
+                    view_box.width(),
+                    view_box.height(),
+                ),
+            )
+            .set(keys::WIDTH, size[0])
+            .set(keys::HEIGHT, size[1]);
+        let (definitions, replaced) = self.definitions();
+        let mut output = Output {
+            markers: self
+                .markers
+                .iter()
+                .map(|marker| Element::<Marker>::new(Rc::clone(marker)))
+                .map(|marker| (marker.get(keys::MARKER_ID), marker))
+                .collect(),
+            replaced,
+            definitions,
+            defined: HashSet::new(),
+            clips: 0,
+        };
+        let inherited = raw::Attributes::new();
+        for symbol in self.symbols.iter() {
+            let mut definition = raw::Symbol::new()
+                .set(keys::SYMBOL_ID, symbol.id())
+                .set(keys::OVERFLOW, "visible");
+            for item in symbol.items() {
+                definition.append(output.render(item, &inherited));
+            }
+            output.definitions.append(definition);
+        }
+        let mut group = raw::Group::new().set(keys::TRANSFORM, FLIP_Y);
+        for item in self.elements.iter() {
+            group.append(output.render(item, &inherited));
+        }
+        let definitions = output.definitions;
+        if !definitions.get_children().is_empty() {
+            document.append(definitions);
+        }
+        document.append(group);
+        Ok(document)
+    }
+
+    /// Marker definitions, where identical markers are only defined once. The returned map
+    /// tells which id the dropped duplicates are replaced with.
+    fn definitions(&self) -> (raw::Definitions, HashMap<String, String>) {
+        let mut definitions = raw::Definitions::new();
+        let mut kept = HashMap::<String, String>::new();
+        let mut replaced = HashMap::new();
+        for marker in self.markers.iter() {
+            let marker = marker.borrow().clone();
+            let id = marker.get_attributes()[keys::MARKER_ID].to_string();
+            let mut anonymous = marker.clone();
+            anonymous.get_attributes_mut().remove(keys::MARKER_ID);
+            match kept.entry(anonymous.to_string()) {
+                Entry::Occupied(entry) => {
+                    replaced.insert(id, entry.get().clone());
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(id);
+                    definitions.append(marker);
+                }
+            }
+        }
+        (definitions, replaced)
     }
 
-    pub fn save<P: AsRef<std::path::Path>>(self, path: P) {
-        let document = self.finalize();
-        svg::save(path, &document).expect("failed to save to svg");
+    pub fn save<P: AsRef<std::path::Path>>(self, path: P) -> Result<(), Error> {
+        let document = self.finalize()?;
+        svg::save(path, &document)?;
+        Ok(())
     }
 
     #[cfg(feature = "pdf")]
-    pub fn save_pdf<P: AsRef<std::path::Path>>(self, path: P) {
-        let document = self.finalize();
+    pub fn save_pdf<P: AsRef<std::path::Path>>(self, path: P) -> Result<(), Error> {
+        let document = self.finalize()?;
         let pdf = svg2pdf::convert_str(&document.to_string(), svg2pdf::Options::default())
-            .expect("invalid svg string");
-        std::fs::write(path, pdf).expect("failed to save to svg");
+            .map_err(|err| Error::Pdf(err.to_string()))?;
+        std::fs::write(path, pdf)?;
+        Ok(())
+    }
+}
+
+/// State of `Document::finalize` while the elements are copied into the SVG document.
+struct Output {
+    markers: HashMap<String, Element<Marker>>,
+    replaced: HashMap<String, String>,
+    definitions: raw::Definitions,
+    defined: HashSet<String>,
+    clips: usize,
+}
+
+impl Output {
+    fn render(&mut self, item: &Item, inherited: &raw::Attributes) -> raw::Element {
+        match item {
+            Item::Element(elem) => self.render_element(elem, inherited),
+            Item::Scope(scope) => self.render_scope(scope, inherited),
+            Item::Instance(instance) => instance.element().to_raw(),
+        }
+    }
+
+    fn render_element(&mut self, elem: &ElemRef, inherited: &raw::Attributes) -> raw::Element {
+        // only the copy is modified, so handles keep the geometry they were given
+        let element = inherit(elem, inherited);
+        let stroke_width = element.get_or(keys::STROKE_WIDTH, DEFAULT_STROKE_WIDTH);
+        let color: String = element.get(keys::STROKE);
+        let mut retreats = [0.0; 3];
+        for (key, retreat) in keys::MARKERS.iter().zip(retreats.iter_mut()) {
+            let Some(id) = marker_reference(&element, key) else {
+                continue;
+            };
+            // duplicates are replaced by the identical marker defined first
+            let id = self.replaced.get(&id).cloned().unwrap_or(id);
+            let Some(marker) = self.markers.get(&id) else {
+                continue;
+            };
+            let marker = colored(marker, &color);
+            let variant: String = marker.get(keys::MARKER_ID);
+            // tips are drawn past the end of the line, see `Tip`
+            *retreat = stroke_width * marker.get::<Scalar>(keys::MARKER_WIDTH);
+            element.insert(*key, format!("url(#{})", variant));
+            // the markers themselves are defined already, only the colored variants are new
+            if variant != id && self.defined.insert(variant) {
+                self.definitions.append(marker.to_raw());
+            }
+        }
+
+        let [start, _, end] = retreats;
+        let name = element.elem.borrow().get_name().clone();
+        match name.as_str() {
+            raw::tag::Line => Element::<Line>::new(Rc::clone(&element.elem)).shorten(start, end),
+            raw::tag::Path => Element::<Path>::new(Rc::clone(&element.elem)).shorten(start, end),
+            _ => {}
+        }
+        element.to_raw()
+    }
+
+    fn render_scope(&mut self, scope: &Scope, inherited: &raw::Attributes) -> raw::Element {
+        let inherited = passed_on(scope, inherited);
+        let mut group = scope.group().to_raw();
+        // the children refer to the variants of the markers in their own color instead
+        for key in keys::MARKERS {
+            group.get_attributes_mut().remove(*key);
+        }
+        if let Some(region) = scope.clip_region() {
+            let id = format!("clip-{}", self.clips);
+            self.clips += 1;
+            let rectangle = raw::Rectangle::new()
+                .set(keys::X, region.min[0])
+                .set(keys::Y, region.min[1])
+                .set(keys::WIDTH, region.width())
+                .set(keys::HEIGHT, region.height());
+            self.definitions.append(
+                raw::ClipPath::new()
+                    .set(keys::CLIP_ID, id.as_str())
+                    .add(rectangle),
+            );
+            group.assign(keys::CLIP_PATH, format!("url(#{})", id));
+        }
+        for item in scope.items() {
+            let child = self.render(item, &inherited);
+            group.append(child);
+        }
+        group
+    }
+}
+
+// presentation attributes that the document reads from the elements, which are inherited from
+// their scopes in SVG
+fn inheritable() -> impl Iterator<Item = &'static str> {
+    [keys::STROKE, keys::STROKE_WIDTH]
+        .into_iter()
+        .chain(keys::MARKERS.iter().copied())
+}
+
+/// The inheritable attributes for the children of the scope, its own ones override the ones it
+/// inherits itself.
+fn passed_on(scope: &Scope, inherited: &raw::Attributes) -> raw::Attributes {
+    let group = scope.group().to_raw();
+    let own = group.get_attributes();
+    let mut attributes = inherited.clone();
+    attributes
+        .extend(inheritable().filter_map(|key| Some((key.to_string(), own.get(key)?.clone()))));
+    attributes
+}
+
+/// Copy of the element with the attributes it inherits written out.
+fn inherit(elem: &ElemRef, inherited: &raw::Attributes) -> Element<()> {
+    let mut copy = elem.borrow().clone();
+    let attributes = copy.get_attributes_mut();
+    for (key, value) in inherited {
+        attributes
+            .entry(key.clone())
+            .or_insert_with(|| value.clone());
+    }
+    Element::new(Rc::new(RefCell::new(copy)))
+}
+
+/// Id of the marker referenced by the given marker attribute of the element.
+fn marker_reference(element: &Element<()>, key: &str) -> Option<String> {
+    let reference: String = element.get(key);
+    let id = reference.strip_prefix("url(#")?.strip_suffix(')')?;
+    Some(id.to_string())
+}
+
+/// Variant of the marker drawn in the stroke color of the element using it.
+fn colored(marker: &Element<Marker>, color: &str) -> Element<Marker> {
+    let fill: String = marker.get(keys::FILL);
+    let stroke: String = marker.get(keys::STROKE);
+    if color.is_empty() || (fill == color && stroke == color) {
+        return marker.clone();
+    }
+    let id: String = marker.get(keys::MARKER_ID);
+    let variant = Element::new(Rc::new(RefCell::new(marker.to_raw())));
+    variant.insert_multi([keys::FILL, keys::STROKE].into_iter().zip([color, color]));
+    variant.insert(
+        keys::MARKER_ID,
+        format!("{}-{}", id, color.trim_start_matches('#')),
+    );
+    variant
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::style::{Color, Stroke, Style};
+    use std::ops::Deref;
+
+    fn view_box(document: raw::Document) -> String {
+        document
+            .get_attributes()
+            .get(keys::VIEW_BOX)
+            .unwrap()
+            .deref()
+            .to_string()
+    }
+
+    #[test]
+    fn view_box_from_elements() {
+        let mut document = Document::new();
+        document.border(0.0);
+        document.circle().at(Vector2::new(-10.0, 0.0)).radius(5.0);
+        document
+            .rectangle()
+            .width(20.0)
+            .height(10.0)
+            .at(Vector2::new(100.0, 20.0));
+        document
+            .line()
+            .start(Vector2::new(0.0, -30.0))
+            .end(Vector2::new(10.0, 0.0));
+        assert_eq!(
+            document.bounding_box(),
+            Some(BoundingBox::new(
+                Vector2::new(-15.0, -30.0),
+                Vector2::new(110.0, 25.0)
+            ))
+        );
+
+        document.border(5.0);
+        let document = document.finalize().unwrap();
+        assert_eq!(
+            document.get_attributes().get(keys::WIDTH).unwrap().deref(),
+            "135"
+        );
+        assert_eq!(
+            document.get_attributes().get(keys::HEIGHT).unwrap().deref(),
+            "65"
+        );
+        assert_eq!(view_box(document), "-20 -30 135 65");
+    }
+
+    #[test]
+    fn view_box_with_stroke_and_markers() {
+        let mut document = Document::new();
+        document.border(0.0);
+        let marker = document.marker().arrow().width(4.0).height(2.0);
+        let stroke = Stroke::new()
+            .color(Color::Black)
+            .width(2.0)
+            .marker_end(marker.id().unwrap());
+        let line = document
+            .line()
+            .start(Vector2::zeros())
+            .end(Vector2::new(10.0, 0.0))
+            .with_style(&Style::new().stroke(stroke));
+        // half of the stroke width plus the marker size times the stroke width
+        let bbox = document.bounding_box().unwrap();
+        assert_eq!(bbox.min, Vector2::new(-9.0, -9.0));
+        assert_eq!(bbox.max, Vector2::new(19.0, 9.0));
+        assert_eq!(document.stroke_bounding_box(&line), Some(bbox));
+        assert_eq!(line.bounding_box().max, Vector2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn markers() {
+        let mut document = Document::new();
+        let small = document.marker().arrow().width(2.0);
+        let large = document.marker().arrow().width(5.0);
+        let duplicate = document.marker().arrow().width(2.0);
+        assert_ne!(small.id().unwrap(), large.id().unwrap());
+        assert_ne!(small.id().unwrap(), duplicate.id().unwrap());
+
+        for marker in [&small, &large, &duplicate] {
+            let stroke = Stroke::new()
+                .color(Color::Black)
+                .marker_end(marker.id().unwrap());
+            document.line().with_style(&Style::new().stroke(stroke));
+        }
+        let svg = document.finalize().unwrap();
+        let definitions = svg.get_children()[0].to_string();
+        assert!(definitions.starts_with("<defs>"));
+        assert_eq!(definitions.matches("<marker").count(), 2);
+        assert!(definitions.contains(&format!("id=\"{}\"", small.id().unwrap())));
+        assert!(definitions.contains(&format!("id=\"{}\"", large.id().unwrap())));
+
+        // the duplicate is replaced by the identical marker defined first, and black strokes
+        // need no variant of the black markers
+        let group = svg.get_children().last().unwrap().to_string();
+        let references = |id: MarkerId| group.matches(&format!("url(#{})", id)).count();
+        assert_eq!(references(small.id().unwrap()), 2);
+        assert_eq!(references(large.id().unwrap()), 1);
+        assert_eq!(references(duplicate.id().unwrap()), 0);
+    }
+
+    #[test]
+    fn markers_follow_the_stroke() {
+        let mut document = Document::new();
+        let tip = document.marker().tip(Tip::stealth().length(4.0));
+        let red = Stroke::new()
+            .color(Color::Red)
+            .width(2.0)
+            .marker_end(tip.id().unwrap());
+        let line = document
+            .line()
+            .start(Vector2::zeros())
+            .end(Vector2::new(10.0, 0.0))
+            .with_style(&Style::new().stroke(red));
+        let blue = Stroke::new()
+            .color(Color::Rgb(0, 0, 255))
+            .marker_start(tip.id().unwrap())
+            .marker_end(tip.id().unwrap());
+        document
+            .path(
+                PathBuilder::start(Vector2::zeros())
+                    .vline(10.0)
+                    .hline(10.0)
+                    .end(),
+            )
+            .with_style(&Style::new().stroke(blue));
+
+        let svg = document.finalize().unwrap();
+        let definitions = svg.get_children()[0].to_string();
+        // the tip itself and its variants in the two colors
+        assert_eq!(definitions.matches("<marker").count(), 3);
+        assert!(definitions.contains(&format!("fill=\"red\" id=\"{}-red\"", tip.id().unwrap())));
+        assert!(definitions.contains(&format!(
+            "fill=\"#0000FF\" id=\"{}-0000FF\"",
+            tip.id().unwrap()
+        )));
+
+        // the drawn line ends where the tip starts, scaled by the stroke width
+        assert_eq!(attribute(&svg, 0, keys::X2), 2.0);
+        assert_eq!(attribute(&svg, 0, keys::X1), 0.0);
+        let group = svg.get_children().last().unwrap().to_string();
+        assert!(group.contains("d=\"M 0 4 L 0 10 L 6 10\""));
+        // while the handle keeps the end given by the user
+        assert_eq!(line.get::<Scalar>(keys::X2), 10.0);
+    }
+
+    #[test]
+    fn markers_without_stroke_width() {
+        let mut document = Document::new();
+        document.border(0.0);
+        let tip = document.marker().tip(Tip::stealth().length(4.0));
+        let line = document
+            .line()
+            .start(Vector2::zeros())
+            .end(Vector2::new(10.0, 0.0));
+        line.insert(keys::STROKE, "black");
+        line.insert(keys::MARKERS[2], format!("url(#{})", tip.id().unwrap()));
+
+        // SVG draws the stroke and the markers one unit wide
+        let bbox = document.bounding_box().unwrap();
+        assert!(bbox.max[0] > 10.5);
+        let svg = document.finalize().unwrap();
+        assert_eq!(attribute(&svg, 0, keys::X2), 6.0);
+    }
+
+    #[test]
+    fn view_box_overrides() {
+        let mut document = Document::new();
+        assert_eq!(view_box(Document::new().finalize().unwrap()), "-5 -5 10 10");
+
+        document.circle().radius(10.0);
+        document
+            .crop(BoundingBox::new(Vector2::zeros(), Vector2::new(4.0, 2.0)))
+            .canvas(400.0, 200.0);
+        let document = document.finalize().unwrap();
+        assert_eq!(
+            document.get_attributes().get(keys::WIDTH).unwrap().deref(),
+            "400"
+        );
+        assert_eq!(view_box(document), "0 -2 4 2");
+    }
+
+    #[test]
+    fn path() {
+        let mut document = Document::new();
+        document.border(0.0);
+        let path = document.path(
+            PathBuilder::start(Vector2::new(-10.0, 0.0))
+                .vline(-20.0)
+                .hline_to(30.0)
+                .end(),
+        );
+        assert_eq!(path.get::<String>(keys::FILL), "none");
+        assert_eq!(path.end_point(), Some(Vector2::new(30.0, -20.0)));
+        assert_eq!(
+            document.bounding_box(),
+            Some(BoundingBox::new(
+                Vector2::new(-10.0, -20.0),
+                Vector2::new(30.0, 0.0)
+            ))
+        );
+
+        let path = path.with_style(&Style::new().fill(Color::Red));
+        assert_eq!(path.get::<String>(keys::FILL), "red");
+    }
+
+    #[test]
+    fn invalid_path_data() {
+        let mut document = Document::new();
+        document.border(0.0);
+        document.circle().radius(1.0);
+        let marker = document.marker().arrow();
+        let stroke = Stroke::new()
+            .color(Color::Black)
+            .marker_end(marker.id().unwrap());
+        let path = document
+            .path(PathBuilder::start(Vector2::zeros()).hline(10.0).end())
+            .with_style(&Style::new().stroke(stroke));
+        path.insert(keys::PATH, "bogus");
+        assert!(path.path().is_none());
+        assert_eq!(path.end_point(), None);
+
+        // the path is left out instead of failing
+        assert_eq!(
+            document.bounding_box(),
+            Some(BoundingBox::new(
+                -Vector2::repeat(1.0),
+                Vector2::repeat(1.0)
+            ))
+        );
+        let svg = document.finalize().unwrap();
+        assert!(svg.to_string().contains("d=\"bogus\""));
+    }
+
+    #[test]
+    fn connect() {
+        let mut document = Document::new();
+        let circle = document.circle().radius(1.0);
+        let rectangle = document
+            .rectangle()
+            .width(4.0)
+            .height(2.0)
+            .at(Vector2::new(10.0, 0.0));
+        let line = document.connect(&circle, &rectangle);
+        assert!((line.start_point() - Vector2::new(1.0, 0.0)).norm() < 1e-5);
+        assert!((line.end_point() - Vector2::new(8.0, 0.0)).norm() < 1e-5);
+
+        // diagonal connections leave each shape through its own border
+        let ellipse = document
+            .ellipse()
+            .rx(2.0)
+            .ry(1.0)
+            .at(Vector2::new(10.0, 10.0));
+        let line = document.connect(&circle, &ellipse);
+        let direction = Vector2::new(1.0, 1.0).normalize();
+        assert!((line.start_point() - direction).norm() < 1e-5);
+        let end = line.end_point() - ellipse.origin();
+        assert!(((end[0] / 2.0).powi(2) + end[1].powi(2) - 1.0).abs() < 1e-5);
+        assert!((end.normalize() + direction).norm() < 1e-5);
+
+        let triangle = document.triangle(Vector2::new(0.0, -10.0), 2.0);
+        let line = document.connect(&circle, &triangle);
+        assert!((line.end_point() - Vector2::new(0.0, -8.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn route() {
+        let mut document = Document::new();
+        let circle = document.circle().radius(1.0);
+        let rectangle = document
+            .rectangle()
+            .width(4.0)
+            .height(2.0)
+            .at(Vector2::new(10.0, -10.0));
+
+        // leaves the circle to the east and enters the rectangle from the top
+        let path = document
+            .route(&circle, &rectangle, Routing::Hv)
+            .path()
+            .unwrap();
+        assert_eq!(path.len(), 3);
+        assert!((path.cursor(0) - Vector2::new(1.0, 0.0)).norm() < 1e-5);
+        assert!((path.cursor(1) - Vector2::new(10.0, 0.0)).norm() < 1e-5);
+        assert!((path.cursor(2) - Vector2::new(10.0, -9.0)).norm() < 1e-5);
+
+        // back from the bottom of the rectangle, bending halfway
+        let path = document
+            .route(&rectangle, &circle, Routing::Vhv)
+            .path()
+            .unwrap();
+        assert!((path.cursor(0) - Vector2::new(10.0, -9.0)).norm() < 1e-5);
+        assert!((path.cursor(1) - Vector2::new(10.0, -5.0)).norm() < 1e-5);
+        assert!((path.cursor(2) - Vector2::new(0.0, -5.0)).norm() < 1e-5);
+        assert!((path.cursor(3) - Vector2::new(0.0, -1.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn outstanding_handles() {
+        let mut document = Document::new();
+        let circle = document.circle().radius(1.0);
+        assert!(matches!(
+            circle.clone().into_raw(),
+            Err(Error::OutstandingHandle)
+        ));
+
+        let svg = document.finalize().unwrap();
+        assert!(svg.to_string().contains("<circle r=\"1\"/>"));
+        // the document is gone, so this is the last handle
+        assert_eq!(circle.into_raw().unwrap().get_name(), raw::tag::Circle);
+    }
+
+    // maps a point of the document to where it ends up on the rendered image
+    // the point in SVG coordinates, as mapped by the scaling written on the root group
+    fn rendered(document: &raw::Document, point: Vector2) -> Vector2 {
+        let group = document.get_children().last().unwrap().to_string();
+        let factors: Vec<Scalar> = group
+            .strip_prefix("<g transform=\"scale(")
+            .and_then(|rest| rest.split(')').next())
+            .unwrap()
+            .split_whitespace()
+            .map(|factor| factor.parse().unwrap())
+            .collect();
+        point.component_mul(&Vector2::new(factors[0], factors[1]))
+    }
+
+    fn attribute(document: &raw::Document, index: usize, key: &str) -> Scalar {
+        let group = document.get_children().last().unwrap().to_string();
+        let line = group.lines().nth(index + 1).unwrap();
+        let start = line.find(&format!(" {}=\"", key)).unwrap() + key.len() + 3;
+        line[start..].split('"').next().unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn y_axis_points_up() {
+        let mut document = Document::new();
+        let circle = document.circle().at(Vector2::new(10.0, 20.0)).radius(5.0);
+        let label = document
+            .text()
+            .content("above")
+            .valign(VAlign::Bottom)
+            .at(circle.above(2.0));
+        let tip = document.marker().arrow();
+        let arrow = Stroke::new()
+            .color(Color::Black)
+            .marker_end(tip.id().unwrap());
+        document
+            .line()
+            .end(Vector2::new(10.0, 0.0))
+            .with_style(&Style::new().stroke(arrow));
+        // handles may outlive the document
+        let svg = document.finalize().unwrap();
+        let group = svg.get_children().last().unwrap().to_string();
+        assert!(group.starts_with("<g transform=\"scale(1 -1)\">"));
+        let (origin, north, south) = (circle.origin(), circle.north(), circle.south());
+        let label_south = label.south();
+
+        // north is rendered above (lower SVG Y) and south below the center
+        let center = rendered(&svg, origin);
+        assert_eq!(center, Vector2::new(10.0, -20.0));
+        assert_eq!(rendered(&svg, north), Vector2::new(10.0, -25.0));
+        assert_eq!(rendered(&svg, south), Vector2::new(10.0, -15.0));
+        assert_eq!(attribute(&svg, 0, keys::CY), 20.0);
+
+        // the text is flipped back, so its Y attribute is already in SVG coordinates
+        assert_eq!(label_south[1], 27.0);
+        assert_eq!(attribute(&svg, 1, keys::Y), -27.0);
+        assert!(attribute(&svg, 1, keys::Y) < center[1]);
+        let text = group.lines().nth(2).unwrap();
+        assert!(text.contains("transform=\"scale(1 -1)\""));
+
+        // and so are the tips of markers, which are drawn in the coordinates of the line
+        let definitions = svg.get_children()[0].to_string();
+        let path = definitions
+            .lines()
+            .find(|line| line.contains("<path"))
+            .unwrap();
+        assert!(path.contains("transform=\"scale(1 -1)\""));
+    }
+
+    #[test]
+    fn transforms_keep_the_geometry() {
+        let mut document = Document::new();
+        // the flip of the text stays innermost
+        let label = document.text().content("x");
+        let origin = label.origin();
+        let label = label.translate(Vector2::new(1.0, 2.0));
+        assert_eq!(
+            label.get::<String>(keys::TRANSFORM),
+            format!("matrix(1 0 0 1 1 2) {}", FLIP_Y)
+        );
+        assert_eq!(label.origin(), origin + Vector2::new(1.0, 2.0));
+
+        // and so does the rotation of an ellipse, even when it is set later
+        let ellipse = document
+            .ellipse()
+            .rx(2.0)
+            .ry(1.0)
+            .skew(0.0, 0.0)
+            .translate(Vector2::new(0.0, 5.0))
+            .rotation(90.0);
+        assert_eq!(
+            ellipse.get::<String>(keys::TRANSFORM),
+            "matrix(1 0 0 1 0 5) rotate(90 0 0)"
+        );
+        assert!((ellipse.north() - Vector2::new(-1.0, 5.0)).norm() < 1e-5);
+        assert!((ellipse.border(0.0) - Vector2::new(1.0, 5.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn scopes() {
+        let mut document = Document::new();
+        let tip = document.marker().tip(Tip::stealth().length(4.0));
+        let red = Stroke::new()
+            .color(Color::Red)
+            .width(2.0)
+            .marker_end(tip.id().unwrap());
+        let scope = document
+            .scope(|document| {
+                document
+                    .line()
+                    .start(Vector2::zeros())
+                    .end(Vector2::new(10.0, 0.0));
+                document.circle().radius(1.0);
+            })
+            .with_style(&Style::new().stroke(red))
+            .clip(BoundingBox::new(Vector2::zeros(), Vector2::new(5.0, 5.0)))
+            .translate(Vector2::new(1.0, 0.0));
+        document.scope(|_| {});
+        assert_eq!(
+            scope.transformation(),
+            Transform::translation(Vector2::new(1.0, 0.0))
+        );
+
+        let svg = document.finalize().unwrap();
+        let definitions = svg.get_children()[0].to_string();
+        assert!(definitions.contains("<clipPath id=\"clip-0\">"));
+        assert!(definitions.contains("<rect height=\"5\" width=\"5\" x=\"0\" y=\"0\"/>"));
+        assert!(definitions.contains(&format!("id=\"{}-red\"", tip.id().unwrap())));
+
+        let group = svg.get_children().last().unwrap().to_string();
+        let lines: Vec<_> = group.lines().map(str::trim).collect();
+        // the style, transformation and clip are set once on the group
+        assert!(lines[1].starts_with("<g clip-path=\"url(#clip-0)\""));
+        assert!(lines[1].contains("stroke=\"red\""));
+        assert!(lines[1].contains("transform=\"matrix(1 0 0 1 1 0)\""));
+        assert!(!lines[1].contains("marker-end"));
+        // while the children use the marker in the color they inherit and make room for it
+        assert!(lines[2].contains(&format!("marker-end=\"url(#{}-red)\"", tip.id().unwrap())));
+        assert!(lines[2].contains("x2=\"2\""));
+        assert!(lines[3].starts_with("<circle"));
+        assert_eq!(lines[4], "</g>");
+        assert_eq!(lines[5], "<g/>");
+    }
+
+    #[test]
+    fn symbols() {
+        let mut document = Document::new();
+        document.border(0.0);
+        let dot = document.symbol(|document| {
+            document.circle().radius(1.0);
+        });
+        // the definition alone draws nothing
+        assert_eq!(document.bounding_box(), None);
+        document.instance(&dot).at(Vector2::new(5.0, 0.0));
+        document
+            .instance(&dot)
+            .scale(Vector2::repeat(2.0))
+            .at(Vector2::new(-5.0, 0.0));
+        assert_eq!(
+            document.bounding_box(),
+            Some(BoundingBox::new(
+                Vector2::new(-7.0, -2.0),
+                Vector2::new(6.0, 2.0)
+            ))
+        );
+
+        let svg = document.finalize().unwrap();
+        let definitions = svg.get_children()[0].to_string();
+        assert_eq!(definitions.matches("<circle").count(), 1);
+        assert!(definitions.contains("<symbol id=\"symbol-0\" overflow=\"visible\">"));
+        let group = svg.get_children().last().unwrap().to_string();
+        assert!(group.contains("<use href=\"#symbol-0\" transform=\"matrix(1 0 0 1 5 0)\"/>"));
+        assert!(group.contains("<use href=\"#symbol-0\" transform=\"matrix(2 0 0 2 -5 0)\"/>"));
     }
 }
@@ -0,0 +1,188 @@
+use super::{Element, ReprT};
+use crate::anchor::{anchor_ellipse, anchor_transformed, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
+use crate::svgutils::keys;
+use crate::transform::Transform;
+use crate::{Scalar, Vector2};
+use nalgebra::Matrix2;
+
+pub struct Ellipse;
+
+impl ReprT for Ellipse {
+    type Repr = crate::style::Stroke;
+}
+
+struct Geometry {
+    origin: Vector2,
+    radius_x: Scalar,
+    radius_y: Scalar,
+    rotation: Scalar,
+}
+
+impl Element<Ellipse> {
+    pub fn at(self, origin: Vector2) -> Self {
+        let rotation = self.geometry().rotation;
+        self.insert_multi([keys::CX, keys::CY].into_iter().zip(origin.iter().copied()));
+        // the rotation is around the center, so it has to follow it
+        self.rotation(rotation)
+    }
+
+    pub fn rx(self, radius_x: Scalar) -> Self {
+        self.insert(keys::RADIUS_X, radius_x);
+        self
+    }
+
+    pub fn ry(self, radius_y: Scalar) -> Self {
+        self.insert(keys::RADIUS_Y, radius_y);
+        self
+    }
+
+    /// Counterclockwise rotation around the center in degrees. Compass anchors rotate with the
+    /// ellipse, while the angles of border and polar anchors stay those of the document.
+    pub fn rotation(self, rotation: Scalar) -> Self {
+        if rotation == 0.0 {
+            self.set_intrinsic_transform("");
+        } else {
+            let origin = self.geometry().origin;
+            self.set_intrinsic_transform(&format!(
+                "rotate({} {} {})",
+                rotation, origin[0], origin[1]
+            ));
+        }
+        self
+    }
+
+    fn geometry(&self) -> Geometry {
+        let x = self.get(keys::CX);
+        let y = self.get(keys::CY);
+        let rotation = self
+            .intrinsic_transform()
+            .strip_prefix("rotate(")
+            .and_then(|arguments| arguments.split_whitespace().next())
+            .and_then(|angle| angle.parse().ok())
+            .unwrap_or_default();
+
+        Geometry {
+            origin: Vector2::new(x, y),
+            radius_x: self.get(keys::RADIUS_X),
+            radius_y: self.get(keys::RADIUS_Y),
+            rotation,
+        }
+    }
+}
+
+impl Bounded for Element<Ellipse> {
+    fn bounding_box(&self) -> BoundingBox {
+        let geometry = self.geometry();
+        let (s, c) = geometry.rotation.to_radians().sin_cos();
+        let (rx, ry) = (geometry.radius_x, geometry.radius_y);
+        let transform = self.transformation();
+        let shape = transform.linear() * Matrix2::new(c * rx, -s * ry, s * rx, c * ry);
+        BoundingBox::ellipse(transform.apply(geometry.origin), &shape)
+    }
+}
+
+impl AnchorT for Element<Ellipse> {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        let geometry = self.geometry();
+        // the rotation is part of the shape, so it is handled like any other transformation
+        let transform =
+            Transform::rotation(geometry.rotation, geometry.origin).then(&self.transformation());
+        anchor_transformed(anchor, &transform, |anchor| {
+            anchor_ellipse(
+                anchor,
+                geometry.origin,
+                geometry.radius_x,
+                geometry.radius_y,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::close;
+    use crate::svgutils::raw;
+    use std::cell::RefCell;
+    use std::ops::Deref;
+    use std::rc::Rc;
+
+    #[test]
+    fn create_and_modify() {
+        let elem = Rc::new(RefCell::new(raw::Ellipse::new().deref().clone()));
+        let ellipse = Element::<Ellipse>::new(Rc::clone(&elem))
+            .rx(4.0)
+            .ry(2.0)
+            .rotation(90.0)
+            .at(Vector2::new(1.0, -1.0));
+
+        let geometry = ellipse.geometry();
+        assert_eq!(geometry.origin, Vector2::new(1.0, -1.0));
+        assert_eq!(geometry.radius_x, 4.0);
+        assert_eq!(geometry.radius_y, 2.0);
+        assert_eq!(geometry.rotation, 90.0);
+        assert_eq!(
+            elem.borrow()
+                .get_attributes()
+                .get(keys::TRANSFORM)
+                .unwrap()
+                .deref(),
+            "rotate(90 1 -1)"
+        );
+
+        let bbox = ellipse.bounding_box();
+        assert!(close(bbox.min, Vector2::new(-1.0, -5.0)));
+        assert!(close(bbox.max, Vector2::new(3.0, 3.0)));
+
+        let ellipse = ellipse.rotation(0.0);
+        assert!(elem
+            .borrow()
+            .get_attributes()
+            .get(keys::TRANSFORM)
+            .is_none());
+        assert!(close(ellipse.bounding_box().max, Vector2::new(5.0, 1.0)));
+
+        // a skewed ellipse is bounded by the extents of the transformed shape
+        let skewed = ellipse.transform(&Transform::skew(45.0, 0.0));
+        let bbox = skewed.bounding_box();
+        let half = Vector2::new(20.0_f32.sqrt(), 2.0);
+        let center = Vector2::new(0.0, -1.0);
+        assert!(close(bbox.min, center - half));
+        assert!(close(bbox.max, center + half));
+    }
+
+    #[test]
+    fn anchors() {
+        let elem = Rc::new(RefCell::new(raw::Ellipse::new().deref().clone()));
+        let ellipse = Element::<Ellipse>::new(Rc::clone(&elem))
+            .at(Vector2::new(10.0, 0.0))
+            .rx(4.0)
+            .ry(2.0);
+
+        assert!(close(ellipse.north(), Vector2::new(10.0, 2.0)));
+        assert!(close(ellipse.west(), Vector2::new(6.0, 0.0)));
+        // the diagonal anchors are on the boundary, not on the bounding box
+        let northeast = ellipse.northeast() - ellipse.origin();
+        assert!(((northeast[0] / 4.0).powi(2) + (northeast[1] / 2.0).powi(2) - 1.0).abs() < 1e-5);
+        assert!((northeast[0] - northeast[1]).abs() < 1e-5);
+        // polar anchors are measured from the center, as on every other shape
+        let polar = ellipse.anchor(Anchor::Polar {
+            radius: 1.0,
+            angle: 0.0,
+        });
+        assert!(close(polar, Vector2::new(11.0, 0.0)));
+
+        // anchors rotate with the ellipse
+        let ellipse = ellipse.rotation(90.0);
+        assert!(close(ellipse.north(), Vector2::new(8.0, 0.0)));
+        assert!(close(ellipse.east(), Vector2::new(10.0, 4.0)));
+        // but border anchors point in the requested direction, so connections hit the center
+        assert!(close(ellipse.border(0.0), Vector2::new(12.0, 0.0)));
+        assert!(close(ellipse.border(90.0), Vector2::new(10.0, 4.0)));
+        let direction = Vector2::new(1.0, 1.0).normalize();
+        let diagonal = ellipse.border(45.0) - ellipse.origin();
+        assert!((diagonal.normalize() - direction).norm() < 1e-5);
+        assert!(((diagonal[0] / 2.0).powi(2) + (diagonal[1] / 4.0).powi(2) - 1.0).abs() < 1e-5);
+    }
+}
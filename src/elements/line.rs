@@ -1,7 +1,8 @@
 use super::{Element, ReprT};
-use crate::anchor::{anchor_rectangle, Anchor, AnchorT};
+use crate::anchor::{anchor_rectangle, anchor_transformed, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
 use crate::svgutils::keys;
-use crate::Vector2;
+use crate::{Scalar, Vector2};
 
 pub struct Line;
 
@@ -20,6 +21,28 @@ impl Element<Line> {
         self
     }
 
+    pub fn start_point(&self) -> Vector2 {
+        self.geometry().start
+    }
+
+    pub fn end_point(&self) -> Vector2 {
+        self.geometry().end
+    }
+
+    /// Pulls both ends back towards each other, but never beyond the other end.
+    pub(crate) fn shorten(&self, start: Scalar, end: Scalar) {
+        let geometry = self.geometry();
+        let length = (geometry.end - geometry.start).norm();
+        if length == 0.0 {
+            return;
+        }
+        let direction = (geometry.end - geometry.start) / length;
+        let _ = self
+            .clone()
+            .start(geometry.start + direction * start.min(length))
+            .end(geometry.end - direction * end.min(length));
+    }
+
     fn geometry(&self) -> Geometry {
         let x1 = self.get(keys::X1);
         let y1 = self.get(keys::Y1);
@@ -38,18 +61,32 @@ struct Geometry {
     end: Vector2,
 }
 
+impl Bounded for Element<Line> {
+    fn bounding_box(&self) -> BoundingBox {
+        let geometry = self.geometry();
+        let transform = self.transformation();
+        BoundingBox::new(
+            transform.apply(geometry.start),
+            transform.apply(geometry.end),
+        )
+    }
+}
+
 impl AnchorT for Element<Line> {
     fn anchor(&self, anchor: Anchor) -> Vector2 {
         let geometry = self.geometry();
         let origin = (geometry.start + geometry.end) / 2.0;
         let half = geometry.start - origin;
-        anchor_rectangle(anchor, origin, half[0].abs(), half[1].abs())
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            anchor_rectangle(anchor, origin, half[0].abs(), half[1].abs())
+        })
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::assert_relative_eq;
     use crate::svgutils::raw;
     use std::cell::RefCell;
     use std::ops::Deref;
@@ -124,5 +161,24 @@ mod test {
         let geometry = elem.geometry();
         assert!((geometry.start - Vector2::new(10.0, -1.0)).norm() < 1e-6);
         assert!((geometry.end - Vector2::new(-5.0, 2.0)).norm() < 1e-6);
+
+        let bbox = elem.bounding_box();
+        assert_eq!(bbox.min, Vector2::new(-5.0, -1.0));
+        assert_eq!(bbox.max, Vector2::new(10.0, 2.0));
+    }
+
+    #[test]
+    fn shorten() {
+        let elem = Rc::new(RefCell::new(raw::Line::new().deref().clone()));
+        let line = Element::<Line>::new(Rc::clone(&elem))
+            .start(Vector2::new(0.0, 0.0))
+            .end(Vector2::new(3.0, 4.0));
+        line.shorten(1.0, 2.5);
+        assert_relative_eq!(line.geometry().start, Vector2::new(0.6, 0.8));
+        assert_relative_eq!(line.geometry().end, Vector2::new(1.5, 2.0));
+
+        // the ends never pass each other
+        line.shorten(0.0, 10.0);
+        assert_relative_eq!(line.geometry().end, Vector2::new(0.6, 0.8));
     }
 }
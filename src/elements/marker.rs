@@ -1,9 +1,18 @@
-use super::{Element, PathBuilder};
+use super::{Element, PathBuilder, FLIP_Y};
+use crate::style::Color;
 use crate::svgutils::{keys, raw};
 use crate::{Scalar, Vector2};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+const ID_PREFIX: &str = "marker-";
 
 pub struct Marker;
 
+/// Reference to a marker of a `Document`, used by strokes to place the marker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MarkerId(usize);
+
 const DEFAULT_HEIGHT: usize = 3;
 const DEFAULT_WIDTH: usize = 3;
 const DEFAULT_ORIENT: &str = "auto-start-reverse";
@@ -11,29 +20,67 @@ const DEFAULT_ORIENT: &str = "auto-start-reverse";
 //const DEFAULT_REF_Y: usize = 5;
 const DEFAULT_VIEW_BOX: &str = "0 -3 10 10";
 
+// tip sizes are given in multiples of the stroke width
+const DEFAULT_TIP_LENGTH: Scalar = 4.0;
+const DEFAULT_TIP_WIDTH: Scalar = 3.0;
+const STEALTH_NOTCH: Scalar = 0.3;
+
+/// Arrow tip drawn by a marker. The tip is drawn past the end of the line and the line is
+/// shortened by the tip length when the document is finalized, so the tip ends exactly where the
+/// line would.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tip {
+    shape: TipShape,
+    length: Scalar,
+    width: Scalar,
+    fill: Option<Color>,
+    reversed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TipShape {
+    Stealth,
+    Latex,
+    Triangle,
+    OpenTriangle,
+    Bar,
+    Bracket,
+    Diamond,
+    Circle,
+    Square,
+}
+
 impl Element<Marker> {
-    fn with_child(self, id: usize, elem: raw::Element) -> Self {
+    fn with_child(self, mut elem: raw::Element) -> Self {
         self.insert_multi(
             [
-                keys::MARKER_ID,
                 keys::MARKER_HEIGHT,
                 keys::MARKER_WIDTH,
                 keys::MARKER_ORIENT,
                 keys::VIEW_BOX,
+                keys::FILL,
+                keys::STROKE,
                 //keys::REF_X,
                 //keys::REF_Y,
             ]
             .into_iter()
             .zip([
-                raw::Value::from(id),
                 raw::Value::from(DEFAULT_HEIGHT),
                 raw::Value::from(DEFAULT_WIDTH),
                 raw::Value::from(DEFAULT_ORIENT),
                 raw::Value::from(DEFAULT_VIEW_BOX),
+                // the tip inherits its colors, which are replaced by the stroke color of the
+                // element using the marker when the document is finalized
+                raw::Value::from(Color::Black),
+                raw::Value::from(Color::Black),
                 //raw::Value::from(DEFAULT_REF_X),
                 //raw::Value::from(DEFAULT_REF_Y),
             ]),
         );
+        // markers are drawn in the mirrored coordinate system of the element using them
+        elem.get_attributes_mut()
+            .insert(keys::TRANSFORM.into(), FLIP_Y.into());
+        self.elem.borrow_mut().get_children_mut().clear();
         self.add_child(elem);
         self
     }
@@ -54,25 +101,325 @@ impl Element<Marker> {
             .line_to(Vector2::new(10.0, 0.0))
             .line_to(Vector2::new(0.0, -3.0))
             .close()
-            .into_raw();
+            .into_raw()
+            .set(keys::STROKE, "none");
 
-        self.with_child(0, raw::Element::from(path))
+        self.with_child(raw::Element::from(path))
+    }
+
+    pub fn tip(self, tip: Tip) -> Self {
+        let marker = self.with_child(tip.raw());
+        marker.insert_multi(
+            [
+                keys::MARKER_WIDTH,
+                keys::MARKER_HEIGHT,
+                keys::VIEW_BOX,
+                keys::REF_X,
+                keys::REF_Y,
+                keys::OVERFLOW,
+            ]
+            .into_iter()
+            .zip([
+                raw::Value::from(tip.length),
+                raw::Value::from(tip.width),
+                raw::Value::from((0.0, -tip.width / 2.0, tip.length, tip.width)),
+                raw::Value::from(0),
+                raw::Value::from(0),
+                // the outlines of open tips may reach out of the view box
+                raw::Value::from("visible"),
+            ]),
+        );
+        marker
     }
 
     pub fn circle(self) -> Self {
-        todo!() //circle markers on a line
+        self.tip(Tip::circle())
     }
 
     pub fn square(self) -> Self {
-        todo!() // square markers on a line
+        self.tip(Tip::square())
+    }
+
+    /// The id given by the `Document` creating the marker, `None` if it was overwritten with one
+    /// that is not a marker id.
+    pub fn id(&self) -> Option<MarkerId> {
+        self.get::<String>(keys::MARKER_ID).parse().ok()
+    }
+}
+
+impl Tip {
+    fn new(shape: TipShape) -> Self {
+        Self {
+            shape,
+            length: DEFAULT_TIP_LENGTH,
+            width: DEFAULT_TIP_WIDTH,
+            fill: None,
+            reversed: false,
+        }
+    }
+
+    /// Filled arrow with a notched back.
+    pub fn stealth() -> Self {
+        Self::new(TipShape::Stealth)
+    }
+
+    /// Filled arrow with slightly curved sides.
+    pub fn latex() -> Self {
+        Self::new(TipShape::Latex)
+    }
+
+    pub fn triangle() -> Self {
+        Self::new(TipShape::Triangle)
+    }
+
+    /// Triangle outline, not filled unless a fill is given.
+    pub fn open_triangle() -> Self {
+        Self::new(TipShape::OpenTriangle)
+    }
+
+    /// Line perpendicular to the stroke.
+    pub fn bar() -> Self {
+        Self::new(TipShape::Bar)
+    }
+
+    pub fn bracket() -> Self {
+        Self::new(TipShape::Bracket)
+    }
+
+    pub fn diamond() -> Self {
+        Self::new(TipShape::Diamond)
+    }
+
+    pub fn circle() -> Self {
+        Self::new(TipShape::Circle).length(DEFAULT_TIP_WIDTH)
+    }
+
+    pub fn square() -> Self {
+        Self::new(TipShape::Square).length(DEFAULT_TIP_WIDTH)
     }
 
-    pub fn id(&self) -> usize {
-        self.get(keys::MARKER_ID)
+    /// Extent along the stroke.
+    pub fn length(self, length: Scalar) -> Self {
+        Self { length, ..self }
+    }
+
+    /// Extent perpendicular to the stroke.
+    pub fn width(self, width: Scalar) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn fill(self, fill: Color) -> Self {
+        Self {
+            fill: Some(fill),
+            ..self
+        }
+    }
+
+    /// Points the tip backwards, towards the line it is placed on.
+    pub fn reversed(self) -> Self {
+        Self {
+            reversed: true,
+            ..self
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        matches!(
+            self.shape,
+            TipShape::OpenTriangle | TipShape::Bar | TipShape::Bracket
+        )
+    }
+
+    /// The tip occupies `[0, length]` along the stroke, pointing towards positive X.
+    fn raw(&self) -> raw::Element {
+        let (length, half_width) = (self.length, self.width / 2.0);
+        let point = |x: Scalar, y: Scalar| {
+            let x = if self.reversed { length - x } else { x };
+            Vector2::new(x, y)
+        };
+        let mut elem: raw::Element = match self.shape {
+            TipShape::Stealth => PathBuilder::start(point(length, 0.0))
+                .line_to(point(0.0, half_width))
+                .line_to(point(STEALTH_NOTCH * length, 0.0))
+                .line_to(point(0.0, -half_width))
+                .close()
+                .into_raw()
+                .into(),
+            TipShape::Latex => PathBuilder::start(point(length, 0.0))
+                .quad_to(
+                    point(0.6 * length, 0.6 * half_width),
+                    point(0.0, half_width),
+                )
+                .line_to(point(0.0, -half_width))
+                .quad_to(point(0.6 * length, -0.6 * half_width), point(length, 0.0))
+                .close()
+                .into_raw()
+                .into(),
+            TipShape::Triangle | TipShape::OpenTriangle => PathBuilder::start(point(length, 0.0))
+                .line_to(point(0.0, half_width))
+                .line_to(point(0.0, -half_width))
+                .close()
+                .into_raw()
+                .into(),
+            TipShape::Bar => PathBuilder::start(point(length, -half_width))
+                .line_to(point(length, half_width))
+                .end()
+                .into_raw()
+                .into(),
+            TipShape::Bracket => PathBuilder::start(point(0.0, -half_width))
+                .line_to(point(length, -half_width))
+                .line_to(point(length, half_width))
+                .line_to(point(0.0, half_width))
+                .end()
+                .into_raw()
+                .into(),
+            TipShape::Diamond => PathBuilder::start(point(length, 0.0))
+                .line_to(point(length / 2.0, half_width))
+                .line_to(point(0.0, 0.0))
+                .line_to(point(length / 2.0, -half_width))
+                .close()
+                .into_raw()
+                .into(),
+            TipShape::Circle => raw::Ellipse::new()
+                .set(keys::CX, length / 2.0)
+                .set(keys::CY, 0)
+                .set(keys::RADIUS_X, length / 2.0)
+                .set(keys::RADIUS_Y, half_width)
+                .into(),
+            TipShape::Square => raw::Rectangle::new()
+                .set(keys::X, 0)
+                .set(keys::Y, -half_width)
+                .set(keys::WIDTH, length)
+                .set(keys::HEIGHT, self.width)
+                .into(),
+        };
+
+        let attributes = elem.get_attributes_mut();
+        if self.is_open() {
+            // the outline is as wide as the stroke the marker is placed on
+            attributes.insert(keys::STROKE_WIDTH.into(), 1.into());
+            attributes.insert(keys::FILL.into(), "none".into());
+        } else {
+            attributes.insert(keys::STROKE.into(), "none".into());
+        }
+        if let Some(fill) = self.fill {
+            attributes.insert(keys::FILL.into(), fill.into());
+        }
+        elem
+    }
+}
+
+impl MarkerId {
+    pub(crate) fn new(index: usize) -> Self {
+        Self(index)
+    }
+}
+
+impl Display for MarkerId {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}{}", ID_PREFIX, self.0)
+    }
+}
+
+impl FromStr for MarkerId {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix(ID_PREFIX)
+            .and_then(|index| index.parse().ok())
+            .map(Self)
+            .ok_or_else(|| format!("invalid marker id {}", s))
     }
 }
 
 #[cfg(test)]
 mod test {
-    // TODO
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn id() {
+        let id = MarkerId::new(12);
+        assert_eq!(id.to_string(), "marker-12");
+        assert_eq!("marker-12".parse::<MarkerId>(), Ok(id));
+        assert!("12".parse::<MarkerId>().is_err());
+        assert!("marker-x".parse::<MarkerId>().is_err());
+
+        // the id attribute may be overwritten like any other
+        let marker = marker();
+        marker.insert(keys::MARKER_ID, id.to_string());
+        assert_eq!(marker.id(), Some(id));
+        marker.insert(keys::MARKER_ID, "arrow");
+        assert_eq!(marker.id(), None);
+    }
+
+    fn marker() -> Element<Marker> {
+        Element::new(Rc::new(RefCell::new(raw::Marker::new().into())))
+    }
+
+    fn child(marker: &Element<Marker>) -> String {
+        let elem = marker.elem.borrow();
+        assert_eq!(elem.get_children().len(), 1);
+        elem.get_children()[0].to_string()
+    }
+
+    #[test]
+    fn tips() {
+        let marker = marker().tip(Tip::stealth().length(5.0).width(4.0));
+        assert_eq!(marker.get::<Scalar>(keys::MARKER_WIDTH), 5.0);
+        assert_eq!(marker.get::<Scalar>(keys::MARKER_HEIGHT), 4.0);
+        assert_eq!(marker.get::<String>(keys::VIEW_BOX), "0 -2 5 4");
+        // the back of the tip is placed at the end of the line
+        assert_eq!(marker.get::<Scalar>(keys::REF_X), 0.0);
+        assert_eq!(marker.get::<String>(keys::FILL), "black");
+        assert_eq!(
+            child(&marker),
+            "<path d=\"M 5 0 L 0 2 L 1.5 0 L 0 -2 Z\" stroke=\"none\" transform=\"scale(1 -1)\"/>"
+        );
+
+        // a new tip replaces the previous one
+        let marker = marker.tip(Tip::stealth().length(5.0).width(4.0).reversed());
+        assert_eq!(
+            child(&marker),
+            "<path d=\"M 0 0 L 5 2 L 3.5 0 L 5 -2 Z\" stroke=\"none\" transform=\"scale(1 -1)\"/>"
+        );
+        // setting the direction twice keeps it reversed
+        let marker = marker.tip(Tip::stealth().length(5.0).width(4.0).reversed().reversed());
+        assert!(child(&marker).contains("d=\"M 0 0 L 5 2 L 3.5 0 L 5 -2 Z\""));
+
+        let marker = marker.tip(Tip::open_triangle().fill(Color::White));
+        let outline = child(&marker);
+        assert!(outline.contains("fill=\"white\""));
+        assert!(outline.contains("stroke-width=\"1\""));
+        assert!(!outline.contains("stroke=\"none\""));
+
+        let marker = marker.tip(Tip::bar().length(1.0).width(6.0));
+        assert!(child(&marker).contains("d=\"M 1 -3 L 1 3\""));
+    }
+
+    #[test]
+    fn circle_and_square() {
+        let circle = marker().circle();
+        assert_eq!(circle.get::<String>(keys::VIEW_BOX), "0 -1.5 3 3");
+        assert_eq!(
+            child(&circle),
+            "<ellipse cx=\"1.5\" cy=\"0\" rx=\"1.5\" ry=\"1.5\" stroke=\"none\" transform=\"scale(1 -1)\"/>"
+        );
+
+        let square = marker().square();
+        assert_eq!(
+            child(&square),
+            "<rect height=\"3\" stroke=\"none\" transform=\"scale(1 -1)\" width=\"3\" x=\"0\" y=\"-1.5\"/>"
+        );
+
+        for tip in [
+            Tip::latex(),
+            Tip::triangle(),
+            Tip::bracket(),
+            Tip::diamond(),
+        ] {
+            let marker = marker().tip(tip.fill(Color::Red));
+            assert!(child(&marker).contains("fill=\"red\""));
+        }
+    }
 }
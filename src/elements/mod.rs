@@ -1,22 +1,37 @@
 mod circle;
 mod document;
+mod ellipse;
 mod line;
 mod marker;
+mod node;
 mod path;
+mod polygon;
 mod rectangle;
+mod scope;
+mod symbol;
+mod text;
 
 use circle::Circle;
 pub use document::Document;
+use ellipse::Ellipse;
 use line::Line;
 use marker::Marker;
-pub use path::PathBuilder;
+pub use marker::{MarkerId, Tip};
+pub use node::{Node, Shape};
+pub use path::{Path, PathBuilder, Routing};
+use polygon::{Polygon, Polyline};
 use rectangle::Rectangle;
+use scope::Item;
+pub use scope::Scope;
+pub use symbol::{Instance, Symbol};
+use text::Text;
+pub use text::{HAlign, VAlign};
 
+use crate::bounding_box::{Bounded, BoundingBox};
 use crate::style::Style;
-use crate::svgutils::{
-    raw::{self, Node},
-    ToAttributes,
-};
+use crate::svgutils::{keys, raw, ToAttributes};
+use crate::transform::Transform;
+use crate::{Error, Scalar, Vector2};
 use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::ops::Deref;
@@ -25,6 +40,28 @@ use std::str::FromStr;
 
 type ElemRef = Rc<RefCell<raw::Element>>;
 
+// SVG's positive Y points down, so every element is drawn in a group that mirrors the Y axis and
+// elements that would appear upside down (text, marker tips) are mirrored back on their own
+const FLIP_Y: &str = "scale(1 -1)";
+
+/// Geometric extent of the element behind the reference, `None` for elements that are not drawn
+/// on their own.
+fn geometry(elem: &ElemRef) -> Option<BoundingBox> {
+    let name = elem.borrow().get_name().clone();
+    let bbox = match name.as_str() {
+        raw::tag::Circle => Element::<Circle>::new(Rc::clone(elem)).bounding_box(),
+        raw::tag::Ellipse => Element::<Ellipse>::new(Rc::clone(elem)).bounding_box(),
+        raw::tag::Line => Element::<Line>::new(Rc::clone(elem)).bounding_box(),
+        raw::tag::Path => return Element::<Path>::new(Rc::clone(elem)).drawn_bounding_box(),
+        raw::tag::Rectangle => Element::<Rectangle>::new(Rc::clone(elem)).bounding_box(),
+        raw::tag::Text => Element::<Text>::new(Rc::clone(elem)).bounding_box(),
+        raw::tag::Polygon => Element::<Polygon>::new(Rc::clone(elem)).bounding_box(),
+        raw::tag::Polyline => Element::<Polyline>::new(Rc::clone(elem)).bounding_box(),
+        _ => return None,
+    };
+    Some(bbox)
+}
+
 pub struct Element<T> {
     elem: ElemRef,
     ty: PhantomData<T>,
@@ -53,7 +90,7 @@ impl<T> Element<T> {
         self
     }
 
-    pub fn add_child<C: Node>(&self, child: C) {
+    pub fn add_child<C: raw::Node>(&self, child: C) {
         let cloned_ref = Rc::clone(&self.elem);
         let mut element = cloned_ref.borrow_mut();
         let children = element.get_children_mut();
@@ -83,8 +120,11 @@ impl<T> Element<T> {
         self.elem.as_ref().clone().into_inner()
     }
 
-    pub fn into_raw(self) -> raw::Element {
-        Rc::into_inner(self.elem).unwrap().into_inner()
+    /// Moves the underlying element out, which fails if other handles still refer to it.
+    pub fn into_raw(self) -> Result<raw::Element, Error> {
+        Rc::into_inner(self.elem)
+            .map(RefCell::into_inner)
+            .ok_or(Error::OutstandingHandle)
     }
 
     pub fn get<V: FromStr + Default>(&self, key: &str) -> V {
@@ -95,6 +135,87 @@ impl<T> Element<T> {
             .and_then(|x| V::from_str(x.deref()).ok())
             .unwrap_or_default()
     }
+
+    /// Like `get`, but with the value SVG uses when the attribute is missing or invalid.
+    pub fn get_or<V: FromStr>(&self, key: &str, default: V) -> V {
+        let elem = self.elem.borrow();
+        let attributes = elem.get_attributes();
+        attributes
+            .get(key)
+            .and_then(|x| V::from_str(x.deref()).ok())
+            .unwrap_or(default)
+    }
+}
+
+// The transform attribute starts with the transformation set by the user, followed by the one
+// that belongs to the geometry of the element, like the rotation of an ellipse or the flip of text.
+impl<T> Element<T> {
+    /// Transformation set by the user, applied on top of the geometry of the element.
+    pub fn transformation(&self) -> Transform {
+        self.transform_parts().0
+    }
+
+    /// Applies `transform` after the current transformation. Anchors and bounding boxes follow.
+    pub fn transform(self, transform: &Transform) -> Self {
+        let (current, intrinsic) = self.transform_parts();
+        self.write_transform(&current.then(transform), &intrinsic);
+        self
+    }
+
+    pub fn translate(self, delta: Vector2) -> Self {
+        self.transform(&Transform::translation(delta))
+    }
+
+    /// See [`Transform::rotation`].
+    pub fn rotate(self, angle: Scalar, around: Vector2) -> Self {
+        self.transform(&Transform::rotation(angle, around))
+    }
+
+    /// See [`Transform::scaling`].
+    pub fn scale(self, factors: Vector2) -> Self {
+        self.transform(&Transform::scaling(factors))
+    }
+
+    /// See [`Transform::skew`].
+    pub fn skew(self, x_angle: Scalar, y_angle: Scalar) -> Self {
+        self.transform(&Transform::skew(x_angle, y_angle))
+    }
+
+    /// The part of the transform attribute that belongs to the geometry of the element.
+    pub(crate) fn intrinsic_transform(&self) -> String {
+        self.transform_parts().1
+    }
+
+    pub(crate) fn set_intrinsic_transform(&self, intrinsic: &str) {
+        self.write_transform(&self.transformation(), intrinsic);
+    }
+
+    fn transform_parts(&self) -> (Transform, String) {
+        let value: String = self.get(keys::TRANSFORM);
+        match value.split_once(')') {
+            Some((matrix, rest)) if matrix.starts_with("matrix(") => (
+                format!("{})", matrix).parse().unwrap_or_default(),
+                rest.trim().to_string(),
+            ),
+            _ => (Transform::identity(), value),
+        }
+    }
+
+    fn write_transform(&self, transform: &Transform, intrinsic: &str) {
+        let value = match (transform.is_identity(), intrinsic.is_empty()) {
+            (true, true) => {
+                self.elem
+                    .borrow_mut()
+                    .get_attributes_mut()
+                    .remove(keys::TRANSFORM);
+                return;
+            }
+            (true, false) => intrinsic.to_string(),
+            (false, true) => transform.to_string(),
+            (false, false) => format!("{} {}", transform, intrinsic),
+        };
+        self.insert(keys::TRANSFORM, value);
+    }
 }
 
 impl<T: ReprT> Element<T> {
@@ -0,0 +1,265 @@
+use super::polygon;
+use super::{Element, ReprT, Text};
+use crate::anchor::{anchor_circle, anchor_diamond, anchor_ellipse, anchor_rounded_rectangle};
+use crate::anchor::{Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
+use crate::style::{Font, Stroke, Style};
+use crate::svgutils::{keys, raw};
+use crate::{Scalar, Vector2};
+
+const DEFAULT_INNER_SEP: Scalar = 4.0;
+
+/// Outline of a [`Node`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    Rectangle,
+    Circle,
+    Ellipse,
+    Diamond,
+}
+
+impl Shape {
+    pub(crate) fn raw(self) -> raw::Element {
+        match self {
+            Self::Rectangle => raw::Rectangle::new().into(),
+            Self::Circle => raw::Circle::new().into(),
+            Self::Ellipse => raw::Ellipse::new().into(),
+            Self::Diamond => raw::Polygon::new().into(),
+        }
+    }
+}
+
+pub struct Outline;
+
+impl ReprT for Outline {
+    type Repr = Stroke;
+}
+
+/// A shape with a centered label, whose size is computed from the extent of the label.
+#[derive(Clone)]
+pub struct Node {
+    shape: Shape,
+    outline: Element<Outline>,
+    label: Element<Text>,
+    inner_sep: Scalar,
+    minimum_width: Scalar,
+    minimum_height: Scalar,
+}
+
+impl Node {
+    pub(crate) fn new(shape: Shape, outline: Element<Outline>, label: Element<Text>) -> Self {
+        let label = label
+            .halign(super::HAlign::Center)
+            .valign(super::VAlign::Middle);
+        Self {
+            shape,
+            outline,
+            label,
+            inner_sep: DEFAULT_INNER_SEP,
+            minimum_width: 0.0,
+            minimum_height: 0.0,
+        }
+        .update()
+    }
+
+    pub fn text<S: Into<String>>(self, content: S) -> Self {
+        let label = self.label.content(content);
+        Self { label, ..self }.update()
+    }
+
+    pub fn at(self, origin: Vector2) -> Self {
+        let label = self.label.at(origin);
+        Self { label, ..self }.update()
+    }
+
+    pub fn inner_sep(self, inner_sep: Scalar) -> Self {
+        Self { inner_sep, ..self }.update()
+    }
+
+    pub fn minimum_width(self, minimum_width: Scalar) -> Self {
+        Self {
+            minimum_width,
+            ..self
+        }
+        .update()
+    }
+
+    pub fn minimum_height(self, minimum_height: Scalar) -> Self {
+        Self {
+            minimum_height,
+            ..self
+        }
+        .update()
+    }
+
+    /// Only applies to rectangular nodes.
+    pub fn rounded_corners(self, corner_radius: Scalar) -> Self {
+        if self.shape == Shape::Rectangle {
+            self.outline.insert(keys::CORNER_RADIUS, corner_radius);
+        }
+        self
+    }
+
+    pub fn with_style(self, style: &Style<Stroke>) -> Self {
+        let outline = self.outline.with_style(style);
+        Self { outline, ..self }
+    }
+
+    pub fn with_font(self, style: &Style<Font>) -> Self {
+        let label = self.label.with_style(style);
+        Self { label, ..self }.update()
+    }
+
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    pub fn label(&self) -> &Element<Text> {
+        &self.label
+    }
+
+    /// Half width and half height of the outline.
+    fn half_size(&self) -> Vector2 {
+        let content = self.label.extent() + Vector2::repeat(2.0 * self.inner_sep);
+        let minimum = Vector2::new(self.minimum_width, self.minimum_height) / 2.0;
+        let half = match self.shape {
+            Shape::Rectangle => content / 2.0,
+            Shape::Circle => Vector2::repeat(content.norm() / 2.0),
+            // the smallest ellipse with the same aspect ratio that contains the content
+            Shape::Ellipse => content / Scalar::sqrt(2.0),
+            // the smallest diamond with the same aspect ratio that contains the content
+            Shape::Diamond => content,
+        };
+        match self.shape {
+            Shape::Circle => Vector2::repeat(half[0].max(minimum[0]).max(minimum[1])),
+            _ => half.sup(&minimum),
+        }
+    }
+
+    fn update(self) -> Self {
+        let origin = self.label.origin();
+        let half = self.half_size();
+        match self.shape {
+            Shape::Rectangle => {
+                let corner = origin - half;
+                self.outline.insert_multi(
+                    [keys::X, keys::Y, keys::WIDTH, keys::HEIGHT]
+                        .into_iter()
+                        .zip([corner[0], corner[1], 2.0 * half[0], 2.0 * half[1]]),
+                );
+            }
+            Shape::Circle => self.outline.insert_multi(
+                [keys::CX, keys::CY, keys::RADIUS]
+                    .into_iter()
+                    .zip([origin[0], origin[1], half[0]]),
+            ),
+            Shape::Ellipse => self.outline.insert_multi(
+                [keys::CX, keys::CY, keys::RADIUS_X, keys::RADIUS_Y]
+                    .into_iter()
+                    .zip([origin[0], origin[1], half[0], half[1]]),
+            ),
+            Shape::Diamond => {
+                let points = polygon::diamond(origin, 2.0 * half[0], 2.0 * half[1]);
+                self.outline
+                    .insert(keys::POINTS, polygon::format_points(&points));
+            }
+        }
+        self
+    }
+}
+
+impl Bounded for Node {
+    fn bounding_box(&self) -> BoundingBox {
+        let origin = self.label.origin();
+        let half = self.half_size();
+        BoundingBox::new(origin - half, origin + half)
+    }
+}
+
+impl AnchorT for Node {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        let origin = self.label.origin();
+        let half = self.half_size();
+        match self.shape {
+            Shape::Rectangle => {
+                let corner_radius = self.outline.get(keys::CORNER_RADIUS);
+                anchor_rounded_rectangle(anchor, origin, half[0], half[1], corner_radius)
+            }
+            Shape::Circle => anchor_circle(anchor, origin, half[0]),
+            Shape::Ellipse => anchor_ellipse(anchor, origin, half[0], half[1]),
+            Shape::Diamond => anchor_diamond(anchor, origin, half[0], half[1]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::assert_relative_eq;
+    use crate::elements::polygon::Polygon;
+    use std::cell::RefCell;
+    use std::ops::Deref;
+    use std::rc::Rc;
+
+    fn node(shape: Shape) -> Node {
+        let outline = Rc::new(RefCell::new(shape.raw()));
+        let label = Rc::new(RefCell::new(raw::Text::new().deref().clone()));
+        Node::new(shape, Element::new(outline), Element::new(label))
+    }
+
+    #[test]
+    fn rectangle() {
+        // 5 characters with the default 16px font
+        let node = node(Shape::Rectangle)
+            .text("hello")
+            .inner_sep(2.0)
+            .at(Vector2::new(10.0, 20.0));
+        let extent = node.label().extent();
+        let half = (extent + Vector2::repeat(4.0)) / 2.0;
+        assert_relative_eq!(node.origin(), Vector2::new(10.0, 20.0));
+        assert_relative_eq!(node.northeast(), Vector2::new(10.0, 20.0) + half);
+        assert_eq!(node.outline.get::<Scalar>(keys::HEIGHT), 20.0);
+        assert_eq!(
+            node.outline.get::<Scalar>(keys::X),
+            10.0 - extent[0] / 2.0 - 2.0
+        );
+
+        // minimum sizes are respected, but never shrink the node
+        let node = node.minimum_width(200.0).minimum_height(10.0);
+        assert_eq!(node.outline.get::<Scalar>(keys::WIDTH), 200.0);
+        assert_eq!(node.outline.get::<Scalar>(keys::HEIGHT), 20.0);
+        assert_relative_eq!(node.east(), Vector2::new(110.0, 20.0));
+    }
+
+    #[test]
+    fn circle() {
+        let node = node(Shape::Circle).inner_sep(3.0);
+        // empty label, so only the inner separation counts
+        let radius = Scalar::hypot(6.0, 22.0) / 2.0;
+        assert!((node.outline.get::<Scalar>(keys::RADIUS) - radius).abs() < 1e-5);
+        let node = node.minimum_height(100.0);
+        assert_eq!(node.outline.get::<Scalar>(keys::RADIUS), 50.0);
+        assert!((node.south() - Vector2::new(0.0, -50.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn ellipse_and_diamond() {
+        let node = node(Shape::Ellipse).text("ab").inner_sep(0.0);
+        let extent = node.label().extent();
+        // the corners of the label are on the ellipse
+        let corner = extent / 2.0;
+        let rx: Scalar = node.outline.get(keys::RADIUS_X);
+        let ry: Scalar = node.outline.get(keys::RADIUS_Y);
+        assert!(((corner[0] / rx).powi(2) + (corner[1] / ry).powi(2) - 1.0).abs() < 1e-5);
+
+        let node = self::node(Shape::Diamond).text("ab").inner_sep(0.0);
+        assert_relative_eq!(node.east(), Vector2::new(extent[0], 0.0));
+        assert_relative_eq!(node.north(), Vector2::new(0.0, extent[1]));
+        let points: String = node.outline.get(keys::POINTS);
+        assert_eq!(points, "19.2,0 0,16 -19.2,0 0,-16");
+        let bbox = Element::<Polygon>::new(Rc::clone(&node.outline.elem)).bounding_box();
+        assert_relative_eq!(bbox.min, -extent);
+        assert_relative_eq!(bbox.max, extent);
+        assert_eq!(node.bounding_box(), bbox);
+    }
+}
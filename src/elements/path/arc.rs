@@ -0,0 +1,186 @@
+use crate::{Scalar, Vector2, PI};
+
+/// Center parameterization of an SVG elliptical arc, see
+/// <https://www.w3.org/TR/SVG11/implnote.html#ArcImplementationNotes>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EllipticalArc {
+    pub center: Vector2,
+    pub radii: Vector2,
+    /// Rotation of the X axis of the ellipse in radians.
+    pub rotation: Scalar,
+    /// Parametric angle of the start point in radians.
+    pub start: Scalar,
+    /// Swept parametric angle in radians, positive values are counterclockwise.
+    pub sweep: Scalar,
+}
+
+impl EllipticalArc {
+    /// Returns `None` if the arc degenerates into a straight line or is omitted entirely.
+    pub fn from_endpoints(
+        from: Vector2,
+        radii: Vector2,
+        rotation: Scalar,
+        large_arc: bool,
+        sweep: bool,
+        to: Vector2,
+    ) -> Option<Self> {
+        let mut radii = radii.abs();
+        if from == to || radii[0] == 0.0 || radii[1] == 0.0 {
+            return None;
+        }
+        let rotation = rotation * PI / 180.0;
+        let (s, c) = rotation.sin_cos();
+        let half = (from - to) / 2.0;
+        let x1 = Vector2::new(c * half[0] + s * half[1], -s * half[0] + c * half[1]);
+
+        // scale up the radii if there is no solution
+        let lambda = (x1[0] / radii[0]).powi(2) + (x1[1] / radii[1]).powi(2);
+        if lambda > 1.0 {
+            radii *= lambda.sqrt();
+        }
+
+        let (rx2, ry2) = (radii[0].powi(2), radii[1].powi(2));
+        let (x2, y2) = (x1[0].powi(2), x1[1].powi(2));
+        let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+        let coef = sign
+            * ((rx2 * ry2 - rx2 * y2 - ry2 * x2) / (rx2 * y2 + ry2 * x2))
+                .max(0.0)
+                .sqrt();
+        let c1 = coef * Vector2::new(radii[0] * x1[1] / radii[1], -radii[1] * x1[0] / radii[0]);
+        let center = Vector2::new(c * c1[0] - s * c1[1], s * c1[0] + c * c1[1]) + (from + to) / 2.0;
+
+        let u = (x1 - c1).component_div(&radii);
+        let v = (-x1 - c1).component_div(&radii);
+        let start = u[1].atan2(u[0]);
+        let mut delta = v[1].atan2(v[0]) - start;
+        if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        } else if !sweep && delta > 0.0 {
+            delta -= 2.0 * PI;
+        }
+
+        Some(Self {
+            center,
+            radii,
+            rotation,
+            start,
+            sweep: delta,
+        })
+    }
+
+    /// Point of the ellipse at the given parametric angle.
+    pub fn point(&self, angle: Scalar) -> Vector2 {
+        let (s, c) = self.rotation.sin_cos();
+        let local = Vector2::new(self.radii[0] * angle.cos(), self.radii[1] * angle.sin());
+        self.center + Vector2::new(c * local[0] - s * local[1], s * local[0] + c * local[1])
+    }
+
+    /// Derivative of `point` with respect to the parametric angle.
+    pub fn derivative(&self, angle: Scalar) -> Vector2 {
+        let (s, c) = self.rotation.sin_cos();
+        let local = Vector2::new(-self.radii[0] * angle.sin(), self.radii[1] * angle.cos());
+        Vector2::new(c * local[0] - s * local[1], s * local[0] + c * local[1])
+    }
+
+    /// Points where the arc reaches its horizontal or vertical extent.
+    pub fn extrema(&self) -> Vec<Vector2> {
+        let (s, c) = self.rotation.sin_cos();
+        let (rx, ry) = (self.radii[0], self.radii[1]);
+        let x_angle = (-ry * s).atan2(rx * c);
+        let y_angle = (ry * c).atan2(rx * s);
+        [x_angle, x_angle + PI, y_angle, y_angle + PI]
+            .into_iter()
+            .filter(|angle| self.contains(*angle))
+            .map(|angle| self.point(angle))
+            .collect()
+    }
+
+    fn contains(&self, angle: Scalar) -> bool {
+        // relative angle in the direction of the sweep, in [0, 2 PI)
+        let relative = (self.sweep.signum() * (angle - self.start)).rem_euclid(2.0 * PI);
+        relative <= self.sweep.abs()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::close;
+
+    #[test]
+    fn half_circle() {
+        let from = Vector2::new(-1.0, 0.0);
+        let to = Vector2::new(1.0, 0.0);
+        let radii = Vector2::new(1.0, 1.0);
+
+        // counterclockwise from west to east goes through the south
+        let arc = EllipticalArc::from_endpoints(from, radii, 0.0, false, true, to).unwrap();
+        assert!(close(arc.center, Vector2::zeros()));
+        assert!((arc.sweep - PI).abs() < 1e-5);
+        assert!(close(arc.point(arc.start), from));
+        assert!(close(arc.point(arc.start + arc.sweep), to));
+        assert!(close(arc.point(arc.start + arc.sweep / 2.0), -Vector2::y()));
+        // the sweep goes through the south, so the arc starts downwards
+        assert!(close(
+            arc.sweep.signum() * arc.derivative(arc.start),
+            -Vector2::y()
+        ));
+
+        let arc = EllipticalArc::from_endpoints(from, radii, 0.0, false, false, to).unwrap();
+        assert!((arc.sweep + PI).abs() < 1e-5);
+        assert!(close(arc.point(arc.start + arc.sweep / 2.0), Vector2::y()));
+    }
+
+    #[test]
+    fn radii_are_scaled_up() {
+        let from = Vector2::new(0.0, 0.0);
+        let to = Vector2::new(10.0, 0.0);
+        let arc = EllipticalArc::from_endpoints(from, Vector2::new(1.0, 1.0), 0.0, false, true, to)
+            .unwrap();
+        assert!(close(arc.radii, Vector2::new(5.0, 5.0)));
+        assert!(close(arc.center, Vector2::new(5.0, 0.0)));
+    }
+
+    #[test]
+    fn large_arc_and_rotation() {
+        let from = Vector2::new(0.0, 0.0);
+        let to = Vector2::new(2.0, 2.0);
+        let radii = Vector2::new(4.0, 2.0);
+        for (large_arc, sweep) in [(false, false), (false, true), (true, false), (true, true)] {
+            let arc =
+                EllipticalArc::from_endpoints(from, radii, 30.0, large_arc, sweep, to).unwrap();
+            assert!(close(arc.point(arc.start), from));
+            assert!(close(arc.point(arc.start + arc.sweep), to));
+            assert_eq!(arc.sweep.abs() > PI, large_arc);
+            assert_eq!(arc.sweep > 0.0, sweep);
+        }
+    }
+
+    #[test]
+    fn degenerate() {
+        let radii = Vector2::new(1.0, 1.0);
+        let point = Vector2::new(1.0, 1.0);
+        assert!(EllipticalArc::from_endpoints(point, radii, 0.0, false, false, point).is_none());
+        assert!(EllipticalArc::from_endpoints(
+            Vector2::zeros(),
+            Vector2::new(0.0, 1.0),
+            0.0,
+            false,
+            false,
+            point
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn extrema() {
+        let from = Vector2::new(-1.0, 0.0);
+        let to = Vector2::new(1.0, 0.0);
+        let radii = Vector2::new(1.0, 1.0);
+        let arc = EllipticalArc::from_endpoints(from, radii, 0.0, false, true, to).unwrap();
+        let extrema = arc.extrema();
+        // west, east and south are reached, north is not
+        assert!(extrema.iter().any(|p| close(*p, -Vector2::y())));
+        assert!(!extrema.iter().any(|p| close(*p, Vector2::y())));
+    }
+}
@@ -0,0 +1,195 @@
+use super::primitive::{self, Primitive};
+use super::Path;
+use crate::{Scalar, Vector2};
+
+// flattened primitive with the path length at each of its points
+struct Piece {
+    primitive: Primitive,
+    points: Vec<(Scalar, Vector2, Scalar)>,
+}
+
+impl Path {
+    /// Length of the drawn path. Curves and arcs are measured on a fine polyline approximation,
+    /// moves do not count.
+    pub fn length(&self) -> Scalar {
+        length(&self.pieces())
+    }
+
+    /// Point at the fraction `t` of the length of the path, `t` is clamped to [0, 1].
+    pub fn point_at(&self, t: Scalar) -> Vector2 {
+        let pieces = self.pieces();
+        self.point_on(&pieces, t.clamp(0.0, 1.0) * length(&pieces))
+    }
+
+    /// Point at the given distance along the path, clamped to its ends.
+    pub fn point_at_length(&self, length: Scalar) -> Vector2 {
+        self.point_on(&self.pieces(), length)
+    }
+
+    /// Unit direction of travel at the fraction `t` of the length, zero if nothing is drawn.
+    pub fn tangent_at(&self, t: Scalar) -> Vector2 {
+        let pieces = self.pieces();
+        tangent_on(&pieces, t.clamp(0.0, 1.0) * length(&pieces))
+    }
+
+    pub fn tangent_at_length(&self, length: Scalar) -> Vector2 {
+        tangent_on(&self.pieces(), length)
+    }
+
+    /// The tangent turned counterclockwise, so it points to the left of the direction of travel.
+    pub fn normal_at(&self, t: Scalar) -> Vector2 {
+        let tangent = self.tangent_at(t);
+        Vector2::new(-tangent[1], tangent[0])
+    }
+
+    pub fn normal_at_length(&self, length: Scalar) -> Vector2 {
+        let tangent = self.tangent_at_length(length);
+        Vector2::new(-tangent[1], tangent[0])
+    }
+
+    // flattened once per query, the helpers below all work on the same pieces
+    fn pieces(&self) -> Vec<Piece> {
+        let mut total = 0.0;
+        primitive::resolve(&self.0)
+            .into_iter()
+            .map(|primitive| {
+                let flattened = primitive.flatten();
+                let mut previous = flattened[0].1;
+                let points = flattened
+                    .into_iter()
+                    .map(|(t, point)| {
+                        total += (point - previous).norm();
+                        previous = point;
+                        (t, point, total)
+                    })
+                    .collect();
+                Piece { primitive, points }
+            })
+            .collect()
+    }
+
+    fn point_on(&self, pieces: &[Piece], length: Scalar) -> Vector2 {
+        match locate(pieces, length) {
+            Some((primitive, t)) => primitive.point(t),
+            None => self.cursor(0),
+        }
+    }
+}
+
+fn length(pieces: &[Piece]) -> Scalar {
+    pieces
+        .last()
+        .and_then(|piece| piece.points.last())
+        .map_or(0.0, |&(_, _, length)| length)
+}
+
+fn tangent_on(pieces: &[Piece], length: Scalar) -> Vector2 {
+    let Some((primitive, t)) = locate(pieces, length) else {
+        return Vector2::zeros();
+    };
+    // the derivative vanishes where control points coincide with the end points
+    let direction = match primitive.derivative(t) {
+        derivative if derivative != Vector2::zeros() => derivative,
+        _ if t < 0.5 => primitive.start_direction(),
+        _ => primitive.end_direction(),
+    };
+    if direction == Vector2::zeros() {
+        direction
+    } else {
+        direction.normalize()
+    }
+}
+
+// primitive and curve parameter at the given length, None if nothing is drawn
+fn locate(pieces: &[Piece], length: Scalar) -> Option<(Primitive, Scalar)> {
+    let last = pieces.last()?;
+    let piece = pieces
+        .iter()
+        .find(|piece| piece.points.last().unwrap().2 >= length)
+        .unwrap_or(last);
+    // NOTE a flattened primitive has at least two points
+    let index = piece
+        .points
+        .iter()
+        .position(|point| point.2 >= length)
+        .unwrap_or(piece.points.len() - 1)
+        .max(1);
+    let (t0, _, s0) = piece.points[index - 1];
+    let (t1, _, s1) = piece.points[index];
+    let ratio = if s1 > s0 {
+        ((length - s0) / (s1 - s0)).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    Some((piece.primitive, t0 + ratio * (t1 - t0)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::close;
+    use crate::elements::PathBuilder;
+
+    #[test]
+    fn lines() {
+        let path = PathBuilder::start(Vector2::zeros())
+            .hline(3.0)
+            .mv(Vector2::new(0.0, 10.0))
+            .vline(4.0)
+            .end();
+        assert_eq!(path.length(), 7.0);
+        assert_eq!(path.point_at(0.0), Vector2::zeros());
+        assert_eq!(path.point_at_length(1.5), Vector2::new(1.5, 0.0));
+        // the move is skipped
+        assert_eq!(path.point_at_length(5.0), Vector2::new(3.0, 12.0));
+        assert_eq!(path.point_at(2.0), Vector2::new(3.0, 14.0));
+        assert_eq!(path.tangent_at(0.0), Vector2::x());
+        assert_eq!(path.tangent_at(1.0), Vector2::y());
+        assert_eq!(path.normal_at_length(1.0), Vector2::y());
+
+        let path = PathBuilder::start(Vector2::new(1.0, 2.0)).end();
+        assert_eq!(path.length(), 0.0);
+        assert_eq!(path.point_at(0.5), Vector2::new(1.0, 2.0));
+        assert_eq!(path.tangent_at(0.5), Vector2::zeros());
+    }
+
+    #[test]
+    fn curves() {
+        let quarter = PathBuilder::start(Vector2::new(1.0, 0.0))
+            .arc(0.0, 90.0, 1.0)
+            .end();
+        assert!((quarter.length() - std::f32::consts::FRAC_PI_2).abs() < 1e-3);
+        let diagonal = Vector2::repeat(0.5_f32.sqrt());
+        assert!(close(quarter.point_at(0.5), diagonal));
+        assert!(close(
+            quarter.tangent_at(0.5),
+            Vector2::new(-diagonal[0], diagonal[1])
+        ));
+        // the normal of a counterclockwise arc points to its center
+        assert!(close(quarter.normal_at(0.5), -diagonal));
+
+        // symmetric curve whose control points coincide with its ends
+        let cubic = PathBuilder::start(Vector2::zeros())
+            .curve_to(
+                Vector2::zeros(),
+                Vector2::new(4.0, 0.0),
+                Vector2::new(4.0, 0.0),
+            )
+            .end();
+        assert!((cubic.length() - 4.0).abs() < 1e-3);
+        assert!(close(cubic.point_at(0.5), Vector2::new(2.0, 0.0)));
+        assert_eq!(cubic.tangent_at(0.0), Vector2::x());
+        assert_eq!(cubic.tangent_at(1.0), Vector2::x());
+
+        let wave = PathBuilder::start(Vector2::zeros())
+            .curve_to(
+                Vector2::new(1.0, 2.0),
+                Vector2::new(2.0, -2.0),
+                Vector2::new(3.0, 0.0),
+            )
+            .end();
+        // the midpoint by length is the center of symmetry
+        assert!(close(wave.point_at(0.5), Vector2::new(1.5, 0.0)));
+        assert!(wave.length() > 3.0);
+    }
+}
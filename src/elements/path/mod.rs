@@ -1,77 +1,429 @@
+mod arc;
+mod geometry;
+mod parse;
+mod primitive;
 mod segment;
+mod transform;
 
+use super::{Element, ReprT};
+use crate::anchor::{anchor_rectangle, anchor_transformed, polar_coordinates, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
 use crate::svgutils::{keys, raw, ToAttributes};
 use crate::{Scalar, Vector2};
+use primitive::Primitive;
 use segment::Segment;
 
+// distance of the control points of `to` curves relative to the distance of the end points, the
+// same as in TikZ
+const LOOSENESS_FACTOR: Scalar = 0.3915;
+
 #[derive(Clone, Debug)]
 pub struct Path(Vec<Segment>);
 
-pub struct PathBuilder(Vec<Segment>);
+pub struct PathBuilder {
+    segments: Vec<Segment>,
+    corner_radius: Scalar,
+}
+
+/// Right-angled routes between two elements, named after their TikZ counterparts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Routing {
+    /// `-|`: horizontal first, then vertical.
+    Hv,
+    /// `|-`: vertical first, then horizontal.
+    Vh,
+    /// `-|-`: horizontal, vertical halfway between the elements, then horizontal again.
+    Hvh,
+    /// `|-|`: vertical, horizontal halfway between the elements, then vertical again.
+    Vhv,
+}
 
 impl PathBuilder {
     pub fn start(start: Vector2) -> Self {
-        Self(vec![Segment::MoveTo(start)])
+        Self {
+            segments: vec![Segment::MoveTo(start)],
+            corner_radius: 0.0,
+        }
+    }
+
+    /// Path from the border of one element to the border of another with right-angled bends.
+    /// The path leaves and enters the elements at the sides facing the direction of travel.
+    pub fn route<A: AnchorT, B: AnchorT>(from: &A, to: &B, routing: Routing) -> Self {
+        let delta = to.origin() - from.origin();
+        // border angles facing the other element horizontally and vertically
+        let east_west = if delta[0] >= 0.0 { 0.0 } else { 180.0 };
+        let north_south = if delta[1] >= 0.0 { 90.0 } else { -90.0 };
+        match routing {
+            Routing::Hv => {
+                let end = to.border(north_south + 180.0);
+                let mut builder = Self::start(from.border(east_west));
+                builder.hv_to(end);
+                builder
+            }
+            Routing::Vh => {
+                let end = to.border(east_west + 180.0);
+                let mut builder = Self::start(from.border(north_south));
+                builder.vh_to(end);
+                builder
+            }
+            Routing::Hvh => {
+                let start = from.border(east_west);
+                let end = to.border(east_west + 180.0);
+                let mut builder = Self::start(start);
+                builder
+                    .hline_to((start[0] + end[0]) / 2.0)
+                    .vline_to(end[1])
+                    .hline_to(end[0]);
+                builder
+            }
+            Routing::Vhv => {
+                let start = from.border(north_south);
+                let end = to.border(north_south + 180.0);
+                let mut builder = Self::start(start);
+                builder
+                    .vline_to((start[1] + end[1]) / 2.0)
+                    .hline_to(end[0])
+                    .vline_to(end[1]);
+                builder
+            }
+        }
+    }
+
+    /// Rounds the corners between straight segments when the path is finished. The corners are
+    /// cut back by at most `radius` along both segments, and by at most half of their length.
+    pub fn rounded_corners(&mut self, radius: Scalar) -> &mut Self {
+        self.corner_radius = radius;
+        self
     }
 
     pub fn mv_to(&mut self, xy: Vector2) -> &mut Self {
-        self.0.push(Segment::MoveTo(xy));
+        self.segments.push(Segment::MoveTo(xy));
         self
     }
 
     pub fn mv(&mut self, dxdy: Vector2) -> &mut Self {
-        self.0.push(Segment::Move(dxdy));
+        self.segments.push(Segment::Move(dxdy));
         self
     }
 
     pub fn line_to(&mut self, xy: Vector2) -> &mut Self {
-        self.0.push(Segment::LineTo(xy));
+        self.segments.push(Segment::LineTo(xy));
         self
     }
 
     pub fn line(&mut self, dxdy: Vector2) -> &mut Self {
-        self.0.push(Segment::Line(dxdy));
+        self.segments.push(Segment::Line(dxdy));
         self
     }
 
     pub fn vline_to(&mut self, y: Scalar) -> &mut Self {
-        self.0.push(Segment::VerticalLineTo(y));
+        self.segments.push(Segment::VerticalLineTo(y));
         self
     }
 
     pub fn vline(&mut self, dy: Scalar) -> &mut Self {
-        self.0.push(Segment::VerticalLine(dy));
+        self.segments.push(Segment::VerticalLine(dy));
         self
     }
 
     pub fn hline_to(&mut self, x: Scalar) -> &mut Self {
-        self.0.push(Segment::HorizontalLineTo(x));
+        self.segments.push(Segment::HorizontalLineTo(x));
         self
     }
 
     pub fn hline(&mut self, dx: Scalar) -> &mut Self {
-        self.0.push(Segment::HorizontalLine(dx));
+        self.segments.push(Segment::HorizontalLine(dx));
         self
     }
 
+    /// Horizontal line, then vertical line to the given point, like `-|` in TikZ.
+    pub fn hv_to(&mut self, xy: Vector2) -> &mut Self {
+        self.hline_to(xy[0]).vline_to(xy[1])
+    }
+
+    /// Vertical line, then horizontal line to the given point, like `|-` in TikZ.
+    pub fn vh_to(&mut self, xy: Vector2) -> &mut Self {
+        self.vline_to(xy[1]).hline_to(xy[0])
+    }
+
     pub fn curve_to(&mut self, x1y1: Vector2, x2y2: Vector2, xy: Vector2) -> &mut Self {
-        self.0.push(Segment::CurveTo(x1y1, x2y2, xy));
+        self.segments.push(Segment::CurveTo(x1y1, x2y2, xy));
         self
     }
 
     pub fn curve(&mut self, dx1dy1: Vector2, dx2dy2: Vector2, dxdy: Vector2) -> &mut Self {
-        self.0.push(Segment::Curve(dx1dy1, dx2dy2, dxdy));
+        self.segments.push(Segment::Curve(dx1dy1, dx2dy2, dxdy));
+        self
+    }
+
+    /// Cubic curve to `target` like `to[out=.., in=..]` in TikZ. The curve leaves the current
+    /// point at `out_angle` and arrives from `in_angle`, both in degrees, where `in_angle` points
+    /// from the target towards the curve. A larger `looseness` moves the control points farther
+    /// out, 1 is the TikZ default.
+    pub fn to(
+        &mut self,
+        target: Vector2,
+        out_angle: Scalar,
+        in_angle: Scalar,
+        looseness: Scalar,
+    ) -> &mut Self {
+        let current = self.current();
+        let distance = LOOSENESS_FACTOR * looseness * (target - current).norm();
+        self.curve_to(
+            current + polar_coordinates(distance, out_angle),
+            target + polar_coordinates(distance, in_angle),
+            target,
+        )
+    }
+
+    /// Curve to `target` leaving the straight line by `angle` degrees to the left.
+    pub fn bend_left(&mut self, target: Vector2, angle: Scalar) -> &mut Self {
+        let direction = self.direction_to(target);
+        self.to(target, direction + angle, direction + 180.0 - angle, 1.0)
+    }
+
+    /// Curve to `target` leaving the straight line by `angle` degrees to the right.
+    pub fn bend_right(&mut self, target: Vector2, angle: Scalar) -> &mut Self {
+        self.bend_left(target, -angle)
+    }
+
+    /// Smooth curve from the current point through all the given points. The tangent at each
+    /// point is parallel to the line between its neighbours; a `tension` of 1 gives a
+    /// Catmull-Rom spline, 0 gives straight lines.
+    pub fn smooth_through(&mut self, points: &[Vector2], tension: Scalar) -> &mut Self {
+        let mut knots = vec![self.current()];
+        knots.extend_from_slice(points);
+        let last = knots.len() - 1;
+        for i in 0..last {
+            let before = knots[i.saturating_sub(1)];
+            let after = knots[(i + 2).min(last)];
+            self.spline_piece(before, knots[i], knots[i + 1], after, tension);
+        }
+        self
+    }
+
+    /// Like `smooth_through`, but the curve returns smoothly to the current point and the
+    /// subpath is closed.
+    pub fn smooth_cycle_through(&mut self, points: &[Vector2], tension: Scalar) -> &mut Self {
+        let mut knots = vec![self.current()];
+        knots.extend_from_slice(points);
+        let count = knots.len();
+        for i in 0..count {
+            self.spline_piece(
+                knots[(i + count - 1) % count],
+                knots[i],
+                knots[(i + 1) % count],
+                knots[(i + 2) % count],
+                tension,
+            );
+        }
+        self.segments.push(Segment::Close);
+        self
+    }
+
+    // cubic piece of a cardinal spline between `from` and `to`
+    fn spline_piece(
+        &mut self,
+        before: Vector2,
+        from: Vector2,
+        to: Vector2,
+        after: Vector2,
+        tension: Scalar,
+    ) {
+        let scale = tension / 6.0;
+        self.curve_to(
+            from + (to - before) * scale,
+            to - (after - from) * scale,
+            to,
+        );
+    }
+
+    /// Cubic curve whose first control point mirrors the last one of the previous curve.
+    pub fn smooth_curve_to(&mut self, x2y2: Vector2, xy: Vector2) -> &mut Self {
+        self.segments.push(Segment::SmoothCurveTo(x2y2, xy));
+        self
+    }
+
+    pub fn smooth_curve(&mut self, dx2dy2: Vector2, dxdy: Vector2) -> &mut Self {
+        self.segments.push(Segment::SmoothCurve(dx2dy2, dxdy));
+        self
+    }
+
+    pub fn quad_to(&mut self, x1y1: Vector2, xy: Vector2) -> &mut Self {
+        self.segments.push(Segment::QuadTo(x1y1, xy));
+        self
+    }
+
+    pub fn quad(&mut self, dx1dy1: Vector2, dxdy: Vector2) -> &mut Self {
+        self.segments.push(Segment::Quad(dx1dy1, dxdy));
+        self
+    }
+
+    /// Quadratic curve whose control point mirrors the one of the previous quadratic curve.
+    pub fn smooth_quad_to(&mut self, xy: Vector2) -> &mut Self {
+        self.segments.push(Segment::SmoothQuadTo(xy));
+        self
+    }
+
+    pub fn smooth_quad(&mut self, dxdy: Vector2) -> &mut Self {
+        self.segments.push(Segment::SmoothQuad(dxdy));
+        self
+    }
+
+    pub fn elliptical_arc_to(
+        &mut self,
+        radii: Vector2,
+        rotation: Scalar,
+        large_arc: bool,
+        sweep: bool,
+        xy: Vector2,
+    ) -> &mut Self {
+        self.segments
+            .push(Segment::ArcTo(radii, rotation, large_arc, sweep, xy));
+        self
+    }
+
+    pub fn elliptical_arc(
+        &mut self,
+        radii: Vector2,
+        rotation: Scalar,
+        large_arc: bool,
+        sweep: bool,
+        dxdy: Vector2,
+    ) -> &mut Self {
+        self.segments
+            .push(Segment::Arc(radii, rotation, large_arc, sweep, dxdy));
+        self
+    }
+
+    /// Circular arc like in TikZ: the current point is at `start_angle` on the circle and the arc
+    /// goes to `end_angle` (in degrees), counterclockwise if `end_angle` is the larger one.
+    pub fn arc(&mut self, start_angle: Scalar, end_angle: Scalar, radius: Scalar) -> &mut Self {
+        let span = end_angle - start_angle;
+        // an arc cannot end where it starts, so full turns are split up
+        let pieces = (span.abs() / 360.0).floor() as usize + 1;
+        let step = span / pieces as Scalar;
+        for i in 0..pieces {
+            let from = start_angle + i as Scalar * step;
+            let dxdy = polar_coordinates(radius, from + step) - polar_coordinates(radius, from);
+            self.segments.push(Segment::Arc(
+                Vector2::repeat(radius),
+                0.0,
+                step.abs() > 180.0,
+                step > 0.0,
+                dxdy,
+            ));
+        }
         self
     }
 
+    // end of the last segment, closing goes back to the start of the subpath
+    fn current(&self) -> Vector2 {
+        let mut current = Vector2::zeros();
+        let mut subpath_start = current;
+        for segment in self.segments.iter() {
+            current = segment.cursor(current).unwrap_or(subpath_start);
+            if matches!(segment, Segment::MoveTo(_) | Segment::Move(_)) {
+                subpath_start = current;
+            }
+        }
+        current
+    }
+
+    // angle of the straight line from the current point to the target, in degrees
+    fn direction_to(&self, target: Vector2) -> Scalar {
+        let delta = target - self.current();
+        delta[1].atan2(delta[0]).to_degrees()
+    }
+
     pub fn end(&mut self) -> Path {
-        Path(std::mem::take(&mut self.0))
+        let segments = std::mem::take(&mut self.segments);
+        if self.corner_radius > 0.0 {
+            Path(round_corners(segments, self.corner_radius))
+        } else {
+            Path(segments)
+        }
     }
 
     pub fn close(&mut self) -> Path {
-        self.0.push(Segment::Close);
-        Path(std::mem::take(&mut self.0))
+        self.segments.push(Segment::Close);
+        self.end()
+    }
+}
+
+// Straight segments are rewritten as absolute lines, and a circular arc is inserted at each corner
+// between two of them. Straight segments without length are dropped, they have no direction.
+fn round_corners(segments: Vec<Segment>, radius: Scalar) -> Vec<Segment> {
+    let mut rounded = Vec::with_capacity(segments.len());
+    let mut current = Vector2::zeros();
+    let mut subpath_start = current;
+    // start of the last straight segment, if it is the last one written
+    let mut straight_from: Option<Vector2> = None;
+    for segment in segments {
+        let straight = matches!(
+            segment,
+            Segment::LineTo(_)
+                | Segment::Line(_)
+                | Segment::VerticalLineTo(_)
+                | Segment::VerticalLine(_)
+                | Segment::HorizontalLineTo(_)
+                | Segment::HorizontalLine(_)
+        );
+        let end = match segment {
+            Segment::Close => subpath_start,
+            // NOTE only Close has no cursor
+            _ => segment.cursor(current).unwrap(),
+        };
+        if straight && end == current {
+            continue;
+        }
+        if straight {
+            let corner = straight_from.and_then(|from| corner(from, current, end, radius));
+            if let Some((before, arc)) = corner {
+                // NOTE the last written segment is the previous straight line
+                *rounded.last_mut().unwrap() = Segment::LineTo(before);
+                rounded.push(arc);
+            }
+            rounded.push(Segment::LineTo(end));
+            straight_from = Some(current);
+        } else {
+            rounded.push(segment);
+            straight_from = None;
+        }
+        if matches!(segment, Segment::MoveTo(_) | Segment::Move(_)) {
+            subpath_start = end;
+        }
+        current = end;
     }
+    rounded
+}
+
+// point where the incoming line stops, and the arc tangent to both lines
+fn corner(from: Vector2, at: Vector2, to: Vector2, radius: Scalar) -> Option<(Vector2, Segment)> {
+    let incoming = at - from;
+    let outgoing = to - at;
+    let lengths = incoming.norm() * outgoing.norm();
+    let cross = incoming.perp(&outgoing);
+    // nothing to round when going straight on or turning back
+    if cross.abs() < 1e-4 * lengths {
+        return None;
+    }
+    let cut = radius.min(incoming.norm() / 2.0).min(outgoing.norm() / 2.0);
+    let before = at - incoming.normalize() * cut;
+    let after = at + outgoing.normalize() * cut;
+    // the tangent length is the radius times tan(turn / 2) = sin / (1 + cos)
+    let arc_radius = cut * (lengths + incoming.dot(&outgoing)) / cross.abs();
+    let counterclockwise = cross > 0.0;
+    Some((
+        before,
+        Segment::ArcTo(
+            Vector2::repeat(arc_radius),
+            0.0,
+            false,
+            counterclockwise,
+            after,
+        ),
+    ))
 }
 
 impl Path {
@@ -107,6 +459,110 @@ impl Path {
         self.to_attributes(attributes);
         path
     }
+
+    /// Absolute position of every end point of the path, and the points where curves and arcs
+    /// reach their horizontal and vertical extent.
+    fn points(&self) -> Vec<Vector2> {
+        let mut points = vec![self.cursor(0)];
+        for primitive in primitive::resolve(&self.0) {
+            points.extend(primitive.extrema());
+            points.push(primitive.end());
+        }
+        // moves are not drawn but they are still part of the path
+        let mut previous = Vector2::zeros();
+        for segment in self.0.iter() {
+            if let Some(cursor) = segment.cursor(previous) {
+                if matches!(segment, Segment::MoveTo(_) | Segment::Move(_)) {
+                    points.push(cursor);
+                }
+                previous = cursor;
+            }
+        }
+        points
+    }
+
+    /// Pulls the start and the end of the path back along its direction to make room for arrow
+    /// tips. The touched segments are rewritten in absolute coordinates, and they are never
+    /// shortened beyond their own chord.
+    pub(crate) fn shorten(&self, start: Scalar, end: Scalar) -> Path {
+        let mut segments = self.0.clone();
+        let last = segments.len() - 1;
+        if end > 0.0 && last > 0 && segments[last] != Segment::Close {
+            let drawn = primitive::resolve(&segments[..last]).len();
+            // a degenerate last segment has no primitive of its own
+            if let Some(primitive) = primitive::resolve(&segments).get(drawn) {
+                let offset = retreat(primitive, primitive.end_direction(), end);
+                segments[last] = match (elevate(*primitive), segments[last]) {
+                    (Primitive::Cubic(_, ctrl1, ctrl2, to), _) => {
+                        Segment::CurveTo(ctrl1, ctrl2 - offset, to - offset)
+                    }
+                    (
+                        Primitive::Arc(_),
+                        Segment::ArcTo(radii, rotation, large_arc, sweep, _)
+                        | Segment::Arc(radii, rotation, large_arc, sweep, _),
+                    ) => {
+                        Segment::ArcTo(radii, rotation, large_arc, sweep, primitive.end() - offset)
+                    }
+                    _ => Segment::LineTo(primitive.end() - offset),
+                };
+            }
+        }
+        if start > 0.0 && last > 0 && segments[1] != Segment::Close {
+            // a smooth segment after the rewritten one would mirror a different control point
+            if let Some(&next) = primitive::resolve(&segments[..3.min(segments.len())]).get(1) {
+                segments[2] = match (segments[2], next) {
+                    (
+                        Segment::SmoothQuadTo(_) | Segment::SmoothQuad(_),
+                        Primitive::Quadratic(_, ctrl, to),
+                    ) => Segment::QuadTo(ctrl, to),
+                    (
+                        Segment::SmoothCurveTo(..) | Segment::SmoothCurve(..),
+                        Primitive::Cubic(_, ctrl1, ctrl2, to),
+                    ) => Segment::CurveTo(ctrl1, ctrl2, to),
+                    (segment, _) => segment,
+                };
+            }
+            if let Some(primitive) = primitive::resolve(&segments[..2]).first() {
+                let offset = retreat(primitive, -primitive.start_direction(), start);
+                segments[0] = Segment::MoveTo(primitive.start() - offset);
+                segments[1] = match (elevate(*primitive), segments[1]) {
+                    (Primitive::Cubic(_, ctrl1, ctrl2, to), _) => {
+                        Segment::CurveTo(ctrl1 - offset, ctrl2, to)
+                    }
+                    (
+                        Primitive::Arc(_),
+                        Segment::ArcTo(radii, rotation, large_arc, sweep, _)
+                        | Segment::Arc(radii, rotation, large_arc, sweep, _),
+                    ) => Segment::ArcTo(radii, rotation, large_arc, sweep, primitive.end()),
+                    _ => Segment::LineTo(primitive.end()),
+                };
+            }
+        }
+        Path(segments)
+    }
+}
+
+// quadratic curves become the identical cubic ones, so that the control point next to a shortened
+// end moves with it and the direction at the other end stays the same
+fn elevate(primitive: Primitive) -> Primitive {
+    match primitive {
+        Primitive::Quadratic(from, ctrl, to) => Primitive::Cubic(
+            from,
+            from + (ctrl - from) * 2.0 / 3.0,
+            to + (ctrl - to) * 2.0 / 3.0,
+            to,
+        ),
+        primitive => primitive,
+    }
+}
+
+// offset along the direction, limited by the distance between the ends of the primitive
+fn retreat(primitive: &Primitive, direction: Vector2, distance: Scalar) -> Vector2 {
+    if direction == Vector2::zeros() {
+        return Vector2::zeros();
+    }
+    let chord = (primitive.end() - primitive.start()).norm();
+    direction.normalize() * distance.min(chord)
 }
 
 impl From<&Path> for raw::Value {
@@ -126,10 +582,78 @@ impl ToAttributes for Path {
     }
 }
 
+impl ReprT for Path {
+    type Repr = crate::style::Stroke;
+}
+
+impl Element<Path> {
+    /// `None` if the path data is not valid, which is only possible when it is set through
+    /// `insert` or `like`.
+    pub fn path(&self) -> Option<Path> {
+        self.get::<String>(keys::PATH).parse().ok()
+    }
+
+    pub fn start_point(&self) -> Option<Vector2> {
+        Some(self.path()?.cursor(0))
+    }
+
+    pub fn end_point(&self) -> Option<Vector2> {
+        let path = self.path()?;
+        Some(path.cursor(path.len() - 1))
+    }
+
+    /// Shortens the drawn path, see `Path::shorten`. Invalid path data is left as it is.
+    pub(crate) fn shorten(&self, start: Scalar, end: Scalar) {
+        if let Some(path) = self.path() {
+            self.insert(keys::PATH, &path.shorten(start, end));
+        }
+    }
+
+    /// Box of the drawn path, `None` if the path data is not valid.
+    pub(crate) fn drawn_bounding_box(&self) -> Option<BoundingBox> {
+        Some(
+            self.path()?
+                .bounding_box()
+                .transform(&self.transformation()),
+        )
+    }
+}
+
+/// Tight box of the drawn curves, control points outside of them are not included.
+impl Bounded for Path {
+    fn bounding_box(&self) -> BoundingBox {
+        // NOTE a path always has at least one point, see `cursor`
+        BoundingBox::from_points(self.points()).unwrap()
+    }
+}
+
+/// Invalid path data draws nothing, its box is the origin.
+impl Bounded for Element<Path> {
+    fn bounding_box(&self) -> BoundingBox {
+        self.drawn_bounding_box()
+            .unwrap_or_else(|| BoundingBox::new(Vector2::zeros(), Vector2::zeros()))
+    }
+}
+
+impl AnchorT for Element<Path> {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        let bbox = self
+            .path()
+            .map(|path| path.bounding_box())
+            .unwrap_or_else(|| BoundingBox::new(Vector2::zeros(), Vector2::zeros()));
+        let half = bbox.size() / 2.0;
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            anchor_rectangle(anchor, bbox.center(), half[0], half[1])
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::cell::RefCell;
     use std::ops::Deref;
+    use std::rc::Rc;
 
     #[test]
     fn build() {
@@ -144,6 +668,14 @@ mod test {
             .vline(0.0)
             .curve_to(Vector2::zeros(), Vector2::zeros(), Vector2::zeros())
             .curve(Vector2::zeros(), Vector2::zeros(), Vector2::zeros())
+            .smooth_curve_to(Vector2::zeros(), Vector2::zeros())
+            .smooth_curve(Vector2::zeros(), Vector2::zeros())
+            .quad_to(Vector2::zeros(), Vector2::zeros())
+            .quad(Vector2::zeros(), Vector2::zeros())
+            .smooth_quad_to(Vector2::zeros())
+            .smooth_quad(Vector2::zeros())
+            .elliptical_arc_to(Vector2::x(), 10.0, true, false, Vector2::zeros())
+            .elliptical_arc(Vector2::y(), -10.0, false, true, Vector2::zeros())
             .close();
 
         assert_eq!(
@@ -160,6 +692,14 @@ mod test {
                 Segment::VerticalLine(0.0),
                 Segment::CurveTo(Vector2::zeros(), Vector2::zeros(), Vector2::zeros()),
                 Segment::Curve(Vector2::zeros(), Vector2::zeros(), Vector2::zeros()),
+                Segment::SmoothCurveTo(Vector2::zeros(), Vector2::zeros()),
+                Segment::SmoothCurve(Vector2::zeros(), Vector2::zeros()),
+                Segment::QuadTo(Vector2::zeros(), Vector2::zeros()),
+                Segment::Quad(Vector2::zeros(), Vector2::zeros()),
+                Segment::SmoothQuadTo(Vector2::zeros()),
+                Segment::SmoothQuad(Vector2::zeros()),
+                Segment::ArcTo(Vector2::x(), 10.0, true, false, Vector2::zeros()),
+                Segment::Arc(Vector2::y(), -10.0, false, true, Vector2::zeros()),
                 Segment::Close,
             ]
         );
@@ -216,4 +756,340 @@ mod test {
             raw::Value::from("M -1.75 -2.5 v 4 h -12.34 m 1 -1 C 100 0, -0 -200, 0 0 Z").deref(),
         );
     }
+
+    #[test]
+    fn element() {
+        let path = PathBuilder::start(Vector2::new(1.0, 2.0))
+            .line(Vector2::new(4.0, 0.0))
+            .curve(
+                Vector2::new(0.0, 3.0),
+                Vector2::new(-2.0, 6.0),
+                Vector2::new(-2.0, -2.0),
+            )
+            .end();
+        let elem = Rc::new(RefCell::new(path.clone().into_raw().deref().clone()));
+        let elem = Element::<Path>::new(Rc::clone(&elem));
+
+        assert_eq!(elem.path().unwrap().segments(), path.segments());
+        assert_eq!(elem.start_point(), Some(Vector2::new(1.0, 2.0)));
+        assert_eq!(elem.end_point(), Some(Vector2::new(3.0, 0.0)));
+
+        // the box is tight around the curve, the control points are farther out
+        let bbox = elem.bounding_box();
+        // the vertical extremum of 2 + 9t - 11t³ is at t² = 3/11
+        let top = 2.0 + 6.0 * (3.0 as Scalar / 11.0).sqrt();
+        assert_eq!(bbox.min, Vector2::new(1.0, 0.0));
+        assert!((bbox.max - Vector2::new(5.0, top)).norm() < 1e-5);
+        assert!((elem.origin() - Vector2::new(3.0, top / 2.0)).norm() < 1e-5);
+        assert!((elem.north() - Vector2::new(3.0, top)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn shorten() {
+        let path = PathBuilder::start(Vector2::zeros())
+            .line(Vector2::new(0.0, 10.0))
+            .hline(10.0)
+            .end();
+        let shortened = path.shorten(2.0, 3.0);
+        assert_eq!(
+            shortened.segments(),
+            &[
+                Segment::MoveTo(Vector2::new(0.0, 2.0)),
+                Segment::LineTo(Vector2::new(0.0, 10.0)),
+                Segment::LineTo(Vector2::new(7.0, 10.0)),
+            ]
+        );
+        // a segment is never shortened beyond its length
+        let shortened = path.shorten(20.0, 0.0);
+        assert_eq!(shortened.cursor(0), Vector2::new(0.0, 10.0));
+
+        // the control points next to the ends move with them
+        let path = PathBuilder::start(Vector2::zeros())
+            .curve_to(
+                Vector2::new(0.0, 5.0),
+                Vector2::new(10.0, 5.0),
+                Vector2::new(10.0, 0.0),
+            )
+            .end();
+        assert_eq!(
+            path.shorten(1.0, 2.0).segments(),
+            &[
+                Segment::MoveTo(Vector2::new(0.0, 1.0)),
+                Segment::CurveTo(
+                    Vector2::new(0.0, 6.0),
+                    Vector2::new(10.0, 7.0),
+                    Vector2::new(10.0, 2.0)
+                ),
+            ]
+        );
+
+        // and so do the ones of quadratic curves, which are drawn as cubic ones
+        let path = PathBuilder::start(Vector2::zeros())
+            .quad_to(Vector2::new(0.0, 6.0), Vector2::new(6.0, 6.0))
+            .end();
+        assert_eq!(
+            path.shorten(1.0, 2.0).segments(),
+            &[
+                Segment::MoveTo(Vector2::new(0.0, 1.0)),
+                Segment::CurveTo(
+                    Vector2::new(0.0, 5.0),
+                    Vector2::new(0.0, 6.0),
+                    Vector2::new(4.0, 6.0)
+                ),
+            ]
+        );
+
+        // smooth curves after a shortened start keep mirroring the original control points
+        let path = PathBuilder::start(Vector2::zeros())
+            .quad_to(Vector2::new(0.0, 6.0), Vector2::new(6.0, 6.0))
+            .smooth_quad(Vector2::new(6.0, -6.0))
+            .smooth_curve_to(Vector2::new(18.0, 0.0), Vector2::new(18.0, 6.0))
+            .end();
+        let shortened = path.shorten(1.0, 0.0);
+        let original = primitive::resolve(path.segments());
+        let resolved = primitive::resolve(shortened.segments());
+        assert_eq!(
+            shortened.segments()[2],
+            Segment::QuadTo(Vector2::new(12.0, 6.0), Vector2::new(12.0, 0.0))
+        );
+        assert_eq!(resolved[1..], original[1..]);
+
+        // the arc keeps its shape parameters
+        let path = PathBuilder::start(Vector2::new(1.0, 0.0))
+            .arc(0.0, 90.0, 1.0)
+            .end();
+        match path.shorten(0.0, 0.5).segments()[1] {
+            Segment::ArcTo(radii, _, false, true, xy) => {
+                assert_eq!(radii, Vector2::repeat(1.0));
+                // the arc ends going west
+                assert!((xy - Vector2::new(0.5, 1.0)).norm() < 1e-5);
+            }
+            _ => panic!("expected an arc"),
+        }
+
+        let path = PathBuilder::start(Vector2::zeros()).close();
+        assert_eq!(path.shorten(1.0, 1.0).segments(), path.segments());
+    }
+
+    #[test]
+    fn orthogonal() {
+        let path = PathBuilder::start(Vector2::zeros())
+            .hv_to(Vector2::new(2.0, 3.0))
+            .vh_to(Vector2::new(5.0, 7.0))
+            .end();
+        assert_eq!(
+            path.segments(),
+            &[
+                Segment::MoveTo(Vector2::zeros()),
+                Segment::HorizontalLineTo(2.0),
+                Segment::VerticalLineTo(3.0),
+                Segment::VerticalLineTo(7.0),
+                Segment::HorizontalLineTo(5.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn rounded_corners() {
+        let path = PathBuilder::start(Vector2::zeros())
+            .hline(10.0)
+            .vline(7.0)
+            .hline(-3.0)
+            .rounded_corners(2.0)
+            .end();
+        assert_eq!(
+            path.segments(),
+            &[
+                Segment::MoveTo(Vector2::zeros()),
+                Segment::LineTo(Vector2::new(8.0, 0.0)),
+                Segment::ArcTo(
+                    Vector2::repeat(2.0),
+                    0.0,
+                    false,
+                    true,
+                    Vector2::new(10.0, 2.0)
+                ),
+                // the short last segment limits the cut of the second corner
+                Segment::LineTo(Vector2::new(10.0, 5.5)),
+                Segment::ArcTo(
+                    Vector2::repeat(1.5),
+                    0.0,
+                    false,
+                    true,
+                    Vector2::new(8.5, 7.0)
+                ),
+                Segment::LineTo(Vector2::new(7.0, 7.0)),
+            ]
+        );
+
+        // degenerate segments are dropped, curves and straight continuations are kept
+        let path = PathBuilder::start(Vector2::zeros())
+            .hline(0.0)
+            .hline(2.0)
+            .hline(2.0)
+            .quad(Vector2::new(1.0, 1.0), Vector2::new(2.0, 0.0))
+            .vline(-2.0)
+            .rounded_corners(1.0)
+            .end();
+        assert_eq!(
+            path.segments(),
+            &[
+                Segment::MoveTo(Vector2::zeros()),
+                Segment::LineTo(Vector2::new(2.0, 0.0)),
+                Segment::LineTo(Vector2::new(4.0, 0.0)),
+                Segment::Quad(Vector2::new(1.0, 1.0), Vector2::new(2.0, 0.0)),
+                Segment::LineTo(Vector2::new(6.0, -2.0)),
+            ]
+        );
+
+        // a clockwise turn at a right angle
+        let path = PathBuilder::start(Vector2::zeros())
+            .vh_to(Vector2::new(4.0, 4.0))
+            .rounded_corners(1.0)
+            .end();
+        assert_eq!(
+            path.segments()[2],
+            Segment::ArcTo(
+                Vector2::repeat(1.0),
+                0.0,
+                false,
+                false,
+                Vector2::new(1.0, 4.0)
+            )
+        );
+    }
+
+    #[test]
+    fn to() {
+        let path = PathBuilder::start(Vector2::zeros())
+            .to(Vector2::new(10.0, 0.0), 90.0, 90.0, 1.0)
+            .end();
+        match path.segments()[1] {
+            Segment::CurveTo(ctrl1, ctrl2, xy) => {
+                assert!((ctrl1 - Vector2::new(0.0, 3.915)).norm() < 1e-5);
+                assert!((ctrl2 - Vector2::new(10.0, 3.915)).norm() < 1e-5);
+                assert_eq!(xy, Vector2::new(10.0, 0.0));
+            }
+            _ => panic!("expected a curve"),
+        }
+
+        // bending is relative to the direction of travel and starts where the path is
+        let path = PathBuilder::start(Vector2::new(1.0, 1.0))
+            .line(Vector2::new(0.0, -1.0))
+            .bend_left(Vector2::new(1.0, 10.0), 30.0)
+            .bend_right(Vector2::new(1.0, 0.0), 30.0)
+            .end();
+        match (path.segments()[2], path.segments()[3]) {
+            (Segment::CurveTo(left, _, _), Segment::CurveTo(right, _, _)) => {
+                // both curves bulge to the west
+                let offset = 0.3915 * 10.0 * Vector2::new(-0.5, 3.0_f32.sqrt() / 2.0);
+                assert!((left - (Vector2::new(1.0, 0.0) + offset)).norm() < 1e-5);
+                assert!(right[0] < 1.0);
+            }
+            _ => panic!("expected curves"),
+        }
+    }
+
+    #[test]
+    fn smooth_through() {
+        let points = [
+            Vector2::new(1.0, 1.0),
+            Vector2::new(2.0, 0.0),
+            Vector2::new(3.0, 1.0),
+        ];
+        let path = PathBuilder::start(Vector2::zeros())
+            .smooth_through(&points, 1.0)
+            .end();
+        assert_eq!(path.len(), 4);
+        for (i, point) in points.iter().enumerate() {
+            assert_eq!(path.cursor(i + 1), *point);
+        }
+        // the tangents at the inner points are shared, so there are no corners
+        match (path.segments()[1], path.segments()[2]) {
+            (Segment::CurveTo(ctrl1, ctrl2, xy), Segment::CurveTo(next, _, _)) => {
+                // the end points are their own neighbours
+                assert_eq!(ctrl1, Vector2::new(1.0, 1.0) / 6.0);
+                assert_eq!(xy - ctrl2, next - xy);
+                assert_eq!(next, Vector2::new(4.0 / 3.0, 1.0));
+            }
+            _ => panic!("expected curves"),
+        }
+
+        // no tension draws straight lines
+        let path = PathBuilder::start(Vector2::zeros())
+            .smooth_through(&points, 0.0)
+            .end();
+        assert_eq!(
+            path.segments()[2],
+            Segment::CurveTo(points[0], points[1], points[1])
+        );
+
+        let path = PathBuilder::start(Vector2::zeros())
+            .smooth_cycle_through(&points, 1.0)
+            .end();
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.segments()[5], Segment::Close);
+        assert_eq!(path.cursor(4), Vector2::zeros());
+        // the closing piece continues the first one smoothly
+        match (path.segments()[4], path.segments()[1]) {
+            (Segment::CurveTo(_, ctrl2, xy), Segment::CurveTo(ctrl1, _, _)) => {
+                assert_eq!(xy - ctrl2, ctrl1 - xy);
+            }
+            _ => panic!("expected curves"),
+        }
+    }
+
+    #[test]
+    fn smooth_curves() {
+        // the implicit control points are reflected, so they reach below the end points
+        let path = PathBuilder::start(Vector2::zeros())
+            .quad_to(Vector2::new(1.0, 2.0), Vector2::new(2.0, 0.0))
+            .smooth_quad(Vector2::new(2.0, 0.0))
+            .smooth_curve_to(Vector2::new(6.0, 1.0), Vector2::new(6.0, 0.0))
+            .end();
+        assert_eq!(path.cursor(3), Vector2::new(6.0, 0.0));
+        let bbox = path.bounding_box();
+        assert_eq!(bbox.min, Vector2::new(0.0, -1.0));
+        assert_eq!(bbox.max, Vector2::new(6.0, 1.0));
+    }
+
+    #[test]
+    fn arc() {
+        let start = Vector2::new(1.0, 0.0);
+        let path = PathBuilder::start(start).arc(0.0, 90.0, 1.0).end();
+        assert_eq!(path.len(), 2);
+        match path.segments()[1] {
+            Segment::Arc(radii, rotation, large_arc, sweep, dxdy) => {
+                assert_eq!(radii, Vector2::repeat(1.0));
+                assert_eq!(rotation, 0.0);
+                assert!(!large_arc);
+                assert!(sweep);
+                assert!((dxdy - Vector2::new(-1.0, 1.0)).norm() < 1e-6);
+            }
+            _ => panic!("expected an arc"),
+        }
+
+        // clockwise and larger than half a turn
+        let path = PathBuilder::start(start).arc(0.0, -270.0, 1.0).end();
+        match path.segments()[1] {
+            Segment::Arc(_, _, large_arc, sweep, _) => assert!(large_arc && !sweep),
+            _ => panic!("expected an arc"),
+        }
+        assert!((path.cursor(1) - Vector2::new(0.0, 1.0)).norm() < 1e-6);
+
+        // full circles are split in two
+        let path = PathBuilder::start(start).arc(0.0, 360.0, 1.0).end();
+        assert_eq!(path.len(), 3);
+        assert!((path.cursor(1) - Vector2::new(-1.0, 0.0)).norm() < 1e-6);
+        assert!((path.cursor(2) - start).norm() < 1e-6);
+        let bbox = path.bounding_box();
+        assert!((bbox.min - Vector2::new(-1.0, -1.0)).norm() < 1e-5);
+        assert!((bbox.max - Vector2::new(1.0, 1.0)).norm() < 1e-5);
+
+        // a quarter arc only covers a quarter of the circle
+        let path = PathBuilder::start(start).arc(0.0, 90.0, 1.0).end();
+        let bbox = path.bounding_box();
+        assert!((bbox.min - Vector2::zeros()).norm() < 1e-5);
+        assert!((bbox.max - Vector2::new(1.0, 1.0)).norm() < 1e-5);
+    }
 }
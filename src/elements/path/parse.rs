@@ -0,0 +1,236 @@
+use super::segment::Segment;
+use super::Path;
+use crate::{Scalar, Vector2};
+use std::str::FromStr;
+
+/// Parses SVG path data, including repeated commands without their letter, numbers that are
+/// not separated when the sign or the decimal point makes it unambiguous, and exponents.
+impl FromStr for Path {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid path data {}", s);
+        let mut scanner = Scanner {
+            data: s.as_bytes(),
+            position: 0,
+        };
+        let mut segments = Vec::new();
+        let mut previous: Option<u8> = None;
+        while !scanner.at_end() {
+            let command = match scanner.command() {
+                Some(command) => command,
+                // coordinates after a move are implicit lines
+                None => match previous {
+                    Some(b'M') => b'L',
+                    Some(b'm') => b'l',
+                    Some(command) => command,
+                    None => return Err(error()),
+                },
+            };
+            segments.push(scanner.segment(command).ok_or_else(error)?);
+            // closing takes no arguments, so it cannot be repeated implicitly
+            previous = (!command.eq_ignore_ascii_case(&b'Z')).then_some(command);
+        }
+        // a relative move at the start is relative to the origin
+        match segments.first() {
+            Some(Segment::MoveTo(_)) => {}
+            Some(Segment::Move(xy)) => segments[0] = Segment::MoveTo(*xy),
+            _ => return Err(error()),
+        }
+        Ok(Self(segments))
+    }
+}
+
+struct Scanner<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn skip_separators(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_whitespace() || c == b',')
+        {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.peek().is_none()
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let command = self.peek().filter(|c| c.is_ascii_alphabetic())?;
+        self.position += 1;
+        Some(command)
+    }
+
+    fn segment(&mut self, command: u8) -> Option<Segment> {
+        let segment = match command {
+            b'M' => Segment::MoveTo(self.vector()?),
+            b'm' => Segment::Move(self.vector()?),
+            b'L' => Segment::LineTo(self.vector()?),
+            b'l' => Segment::Line(self.vector()?),
+            b'V' => Segment::VerticalLineTo(self.number()?),
+            b'v' => Segment::VerticalLine(self.number()?),
+            b'H' => Segment::HorizontalLineTo(self.number()?),
+            b'h' => Segment::HorizontalLine(self.number()?),
+            b'C' => Segment::CurveTo(self.vector()?, self.vector()?, self.vector()?),
+            b'c' => Segment::Curve(self.vector()?, self.vector()?, self.vector()?),
+            b'S' => Segment::SmoothCurveTo(self.vector()?, self.vector()?),
+            b's' => Segment::SmoothCurve(self.vector()?, self.vector()?),
+            b'Q' => Segment::QuadTo(self.vector()?, self.vector()?),
+            b'q' => Segment::Quad(self.vector()?, self.vector()?),
+            b'T' => Segment::SmoothQuadTo(self.vector()?),
+            b't' => Segment::SmoothQuad(self.vector()?),
+            b'A' => Segment::ArcTo(
+                self.vector()?,
+                self.number()?,
+                self.flag()?,
+                self.flag()?,
+                self.vector()?,
+            ),
+            b'a' => Segment::Arc(
+                self.vector()?,
+                self.number()?,
+                self.flag()?,
+                self.flag()?,
+                self.vector()?,
+            ),
+            b'Z' | b'z' => Segment::Close,
+            _ => return None,
+        };
+        Some(segment)
+    }
+
+    fn vector(&mut self) -> Option<Vector2> {
+        Some(Vector2::new(self.number()?, self.number()?))
+    }
+
+    // flags are single digits, so they need no separator
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.peek()? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.position += 1;
+        Some(flag)
+    }
+
+    // the longest prefix that forms a number, so "1-2" and ".5.5" are two numbers each
+    fn number(&mut self) -> Option<Scalar> {
+        self.skip_separators();
+        let start = self.position;
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.position += 1;
+        }
+        let mut digits = self.digits();
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            digits += self.digits();
+        }
+        if digits == 0 {
+            self.position = start;
+            return None;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mantissa_end = self.position;
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            if self.digits() == 0 {
+                self.position = mantissa_end;
+            }
+        }
+        // NOTE the scanned bytes are ASCII
+        std::str::from_utf8(&self.data[start..self.position])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        self.position - start
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::elements::PathBuilder;
+    use crate::svgutils::raw;
+
+    #[test]
+    fn round_trip() {
+        let path = PathBuilder::start(Vector2::new(-1.75, -2.5))
+            .vline(4.0)
+            .hline_to(-12.34)
+            .line(Vector2::new(1e-3, 2.0))
+            .mv(Vector2::new(1.0, -1.0))
+            .curve_to(Vector2::x(), -Vector2::y(), Vector2::zeros())
+            .curve(Vector2::x(), Vector2::y(), Vector2::repeat(2.0))
+            .smooth_curve_to(Vector2::x(), Vector2::y())
+            .smooth_curve(-Vector2::x(), Vector2::y())
+            .quad_to(Vector2::new(0.5, 0.5), Vector2::x())
+            .quad(Vector2::new(0.5, -0.5), Vector2::y())
+            .smooth_quad_to(Vector2::zeros())
+            .smooth_quad(Vector2::x())
+            .elliptical_arc_to(Vector2::new(3.0, 2.0), 15.0, true, false, Vector2::x())
+            .arc(0.0, 90.0, 2.5)
+            .close();
+        let parsed: Path = raw::Value::from(&path).parse().unwrap();
+        assert_eq!(parsed.segments(), path.segments());
+    }
+
+    #[test]
+    fn compact_syntax() {
+        let path: Path = "m1,2L3-4 5.5.5h1e1v-2E-1zM0 0a1 1 0 01.5.5"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            path.segments(),
+            &[
+                Segment::MoveTo(Vector2::new(1.0, 2.0)),
+                Segment::LineTo(Vector2::new(3.0, -4.0)),
+                Segment::LineTo(Vector2::new(5.5, 0.5)),
+                Segment::HorizontalLine(10.0),
+                Segment::VerticalLine(-0.2),
+                Segment::Close,
+                Segment::MoveTo(Vector2::zeros()),
+                Segment::Arc(Vector2::repeat(1.0), 0.0, false, true, Vector2::repeat(0.5)),
+            ]
+        );
+
+        // extra coordinates after a move are lines of the same kind
+        let path: Path = "M 0 0 1 1 m 1 0 0 1".parse().unwrap();
+        assert_eq!(path.segments()[1], Segment::LineTo(Vector2::repeat(1.0)));
+        assert_eq!(path.segments()[3], Segment::Line(Vector2::y()));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!("".parse::<Path>().is_err());
+        assert!("L 1 2".parse::<Path>().is_err());
+        assert!("M 1".parse::<Path>().is_err());
+        assert!("M 1 2 X".parse::<Path>().is_err());
+        assert!("M 1 2 Z 3 4".parse::<Path>().is_err());
+        assert!("M 1 2 A 1 1 0 2 0 3 3".parse::<Path>().is_err());
+        assert_eq!(
+            "M 1 e".parse::<Path>().unwrap_err(),
+            "invalid path data M 1 e"
+        );
+    }
+}
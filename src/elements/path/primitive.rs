@@ -0,0 +1,506 @@
+use super::arc::EllipticalArc;
+use super::segment::Segment;
+use crate::{Scalar, Vector2};
+
+// subdivisions are accepted once the midpoint is this close to the chord
+const FLATNESS: Scalar = 1e-3;
+// curves with an inflection can look flat at a coarse level, so a few levels are always taken
+const MIN_DEPTH: usize = 3;
+const MAX_DEPTH: usize = 16;
+
+/// A drawn piece of a path in absolute coordinates, with all implicit control points resolved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Primitive {
+    Line(Vector2, Vector2),
+    Quadratic(Vector2, Vector2, Vector2),
+    Cubic(Vector2, Vector2, Vector2, Vector2),
+    Arc(EllipticalArc),
+}
+
+impl Primitive {
+    pub fn start(&self) -> Vector2 {
+        match self {
+            Self::Line(from, _) | Self::Quadratic(from, _, _) | Self::Cubic(from, _, _, _) => *from,
+            Self::Arc(arc) => arc.point(arc.start),
+        }
+    }
+
+    pub fn end(&self) -> Vector2 {
+        match self {
+            Self::Line(_, to) | Self::Quadratic(_, _, to) | Self::Cubic(_, _, _, to) => *to,
+            Self::Arc(arc) => arc.point(arc.start + arc.sweep),
+        }
+    }
+
+    /// Direction of travel at the start, not normalized. Zero if the primitive has no extent.
+    pub fn start_direction(&self) -> Vector2 {
+        match self {
+            Self::Line(from, to) => to - from,
+            Self::Quadratic(from, ctrl, to) => first_nonzero([ctrl - from, to - from]),
+            Self::Cubic(from, ctrl1, ctrl2, to) => {
+                first_nonzero([ctrl1 - from, ctrl2 - from, to - from])
+            }
+            Self::Arc(arc) => arc.sweep.signum() * arc.derivative(arc.start),
+        }
+    }
+
+    /// Direction of travel at the end, not normalized. Zero if the primitive has no extent.
+    pub fn end_direction(&self) -> Vector2 {
+        match self {
+            Self::Line(from, to) => to - from,
+            Self::Quadratic(from, ctrl, to) => first_nonzero([to - ctrl, to - from]),
+            Self::Cubic(from, ctrl1, ctrl2, to) => {
+                first_nonzero([to - ctrl2, to - ctrl1, to - from])
+            }
+            Self::Arc(arc) => arc.sweep.signum() * arc.derivative(arc.start + arc.sweep),
+        }
+    }
+
+    /// Point at the curve parameter `t` in [0, 1].
+    pub fn point(&self, t: Scalar) -> Vector2 {
+        let s = 1.0 - t;
+        match self {
+            Self::Line(from, to) => from + (to - from) * t,
+            Self::Quadratic(from, ctrl, to) => s * s * from + 2.0 * s * t * ctrl + t * t * to,
+            Self::Cubic(from, ctrl1, ctrl2, to) => {
+                s * s * s * from
+                    + 3.0 * s * s * t * ctrl1
+                    + 3.0 * s * t * t * ctrl2
+                    + t * t * t * to
+            }
+            Self::Arc(arc) => arc.point(arc.start + t * arc.sweep),
+        }
+    }
+
+    /// Derivative of `point` with respect to `t`.
+    pub fn derivative(&self, t: Scalar) -> Vector2 {
+        let s = 1.0 - t;
+        match self {
+            Self::Line(from, to) => to - from,
+            Self::Quadratic(from, ctrl, to) => 2.0 * s * (ctrl - from) + 2.0 * t * (to - ctrl),
+            Self::Cubic(from, ctrl1, ctrl2, to) => {
+                3.0 * s * s * (ctrl1 - from)
+                    + 6.0 * s * t * (ctrl2 - ctrl1)
+                    + 3.0 * t * t * (to - ctrl2)
+            }
+            Self::Arc(arc) => arc.sweep * arc.derivative(arc.start + t * arc.sweep),
+        }
+    }
+
+    /// Points between the ends where the primitive reaches its horizontal or vertical extent.
+    pub fn extrema(&self) -> Vec<Vector2> {
+        let roots = |axis: usize| -> Vec<Scalar> {
+            match self {
+                Self::Line(..) => Vec::new(),
+                // the derivative is linear
+                Self::Quadratic(from, ctrl, to) => {
+                    let denominator = from[axis] - 2.0 * ctrl[axis] + to[axis];
+                    if denominator == 0.0 {
+                        Vec::new()
+                    } else {
+                        vec![(from[axis] - ctrl[axis]) / denominator]
+                    }
+                }
+                // the derivative is quadratic, a t² + b t + c
+                Self::Cubic(from, ctrl1, ctrl2, to) => {
+                    let a = -from[axis] + 3.0 * ctrl1[axis] - 3.0 * ctrl2[axis] + to[axis];
+                    let b = 2.0 * (from[axis] - 2.0 * ctrl1[axis] + ctrl2[axis]);
+                    let c = ctrl1[axis] - from[axis];
+                    quadratic_roots(a, b, c)
+                }
+                Self::Arc(_) => Vec::new(),
+            }
+        };
+        match self {
+            Self::Arc(arc) => arc.extrema(),
+            _ => [0, 1]
+                .into_iter()
+                .flat_map(roots)
+                .filter(|t| *t > 0.0 && *t < 1.0)
+                .map(|t| self.point(t))
+                .collect(),
+        }
+    }
+
+    /// Polyline approximation as pairs of curve parameter and point, from `t` = 0 to 1.
+    pub fn flatten(&self) -> Vec<(Scalar, Vector2)> {
+        let mut points = vec![(0.0, self.start())];
+        match self {
+            Self::Line(_, to) => points.push((1.0, *to)),
+            _ => self.subdivide(0.0, 1.0, 0, &mut points),
+        }
+        points
+    }
+
+    // appends the points after `from` up to and including `to`
+    fn subdivide(
+        &self,
+        from: Scalar,
+        to: Scalar,
+        depth: usize,
+        points: &mut Vec<(Scalar, Vector2)>,
+    ) {
+        let mid = (from + to) / 2.0;
+        let (a, m, b) = (self.point(from), self.point(mid), self.point(to));
+        let deviation = (m - a).norm() + (b - m).norm() - (b - a).norm();
+        if depth >= MAX_DEPTH || (depth >= MIN_DEPTH && deviation < FLATNESS) {
+            points.extend([(mid, m), (to, b)]);
+        } else {
+            self.subdivide(from, mid, depth + 1, points);
+            self.subdivide(mid, to, depth + 1, points);
+        }
+    }
+}
+
+// real roots of a t² + b t + c, which may degenerate into a linear equation
+fn quadratic_roots(a: Scalar, b: Scalar, c: Scalar) -> Vec<Scalar> {
+    if a.abs() < Scalar::EPSILON {
+        return if b == 0.0 { Vec::new() } else { vec![-c / b] };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+    let root = discriminant.sqrt();
+    vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+}
+
+// control points may coincide with the end points, in which case the tangent is given by the
+// next distinct point
+fn first_nonzero<const N: usize>(candidates: [Vector2; N]) -> Vector2 {
+    candidates
+        .into_iter()
+        .find(|candidate| *candidate != Vector2::zeros())
+        .unwrap_or_else(Vector2::zeros)
+}
+
+// the last control point of the previous segment, needed by the smooth variants
+enum Control {
+    Quadratic(Vector2),
+    Cubic(Vector2),
+    None,
+}
+
+/// Converts the segments of a path into absolute primitives. Moves are not drawn, so they do
+/// not produce a primitive.
+pub fn resolve(segments: &[Segment]) -> Vec<Primitive> {
+    let mut primitives = Vec::with_capacity(segments.len());
+    let mut current = Vector2::zeros();
+    let mut subpath_start = current;
+    let mut control = Control::None;
+
+    for segment in segments {
+        let reflected_quadratic = match control {
+            Control::Quadratic(point) => 2.0 * current - point,
+            _ => current,
+        };
+        let reflected_cubic = match control {
+            Control::Cubic(point) => 2.0 * current - point,
+            _ => current,
+        };
+        control = Control::None;
+
+        let primitive = match *segment {
+            Segment::MoveTo(_) | Segment::Move(_) => None,
+            Segment::LineTo(_)
+            | Segment::Line(_)
+            | Segment::VerticalLineTo(_)
+            | Segment::VerticalLine(_)
+            | Segment::HorizontalLineTo(_)
+            | Segment::HorizontalLine(_) => None,
+            Segment::CurveTo(x1y1, x2y2, xy) => {
+                control = Control::Cubic(x2y2);
+                Some(Primitive::Cubic(current, x1y1, x2y2, xy))
+            }
+            Segment::Curve(dx1dy1, dx2dy2, dxdy) => {
+                control = Control::Cubic(current + dx2dy2);
+                Some(Primitive::Cubic(
+                    current,
+                    current + dx1dy1,
+                    current + dx2dy2,
+                    current + dxdy,
+                ))
+            }
+            Segment::SmoothCurveTo(x2y2, xy) => {
+                control = Control::Cubic(x2y2);
+                Some(Primitive::Cubic(current, reflected_cubic, x2y2, xy))
+            }
+            Segment::SmoothCurve(dx2dy2, dxdy) => {
+                control = Control::Cubic(current + dx2dy2);
+                Some(Primitive::Cubic(
+                    current,
+                    reflected_cubic,
+                    current + dx2dy2,
+                    current + dxdy,
+                ))
+            }
+            Segment::QuadTo(x1y1, xy) => {
+                control = Control::Quadratic(x1y1);
+                Some(Primitive::Quadratic(current, x1y1, xy))
+            }
+            Segment::Quad(dx1dy1, dxdy) => {
+                control = Control::Quadratic(current + dx1dy1);
+                Some(Primitive::Quadratic(
+                    current,
+                    current + dx1dy1,
+                    current + dxdy,
+                ))
+            }
+            Segment::SmoothQuadTo(xy) => {
+                control = Control::Quadratic(reflected_quadratic);
+                Some(Primitive::Quadratic(current, reflected_quadratic, xy))
+            }
+            Segment::SmoothQuad(dxdy) => {
+                control = Control::Quadratic(reflected_quadratic);
+                Some(Primitive::Quadratic(
+                    current,
+                    reflected_quadratic,
+                    current + dxdy,
+                ))
+            }
+            Segment::ArcTo(radii, rotation, large_arc, sweep, _)
+            | Segment::Arc(radii, rotation, large_arc, sweep, _) => {
+                // NOTE arcs always have an end point
+                let end = segment.cursor(current).unwrap();
+                match EllipticalArc::from_endpoints(current, radii, rotation, large_arc, sweep, end)
+                {
+                    Some(arc) => Some(Primitive::Arc(arc)),
+                    // zero radii draw a straight line, identical end points draw nothing
+                    None if end != current => Some(Primitive::Line(current, end)),
+                    None => None,
+                }
+            }
+            Segment::Close => Some(Primitive::Line(current, subpath_start)),
+        };
+
+        let end = match segment {
+            Segment::Close => subpath_start,
+            // NOTE only Close has no cursor
+            _ => segment.cursor(current).unwrap(),
+        };
+        match (segment, primitive) {
+            (Segment::MoveTo(_) | Segment::Move(_), _) => subpath_start = end,
+            (_, Some(primitive)) => primitives.push(primitive),
+            (_, None) if end != current => primitives.push(Primitive::Line(current, end)),
+            _ => {}
+        }
+        current = end;
+    }
+    primitives
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let segments = [
+            Segment::MoveTo(Vector2::new(1.0, 1.0)),
+            Segment::HorizontalLine(2.0),
+            Segment::VerticalLineTo(5.0),
+            Segment::Move(Vector2::new(1.0, 0.0)),
+            Segment::Line(Vector2::new(0.0, 1.0)),
+            Segment::Close,
+        ];
+        assert_eq!(
+            resolve(&segments),
+            vec![
+                Primitive::Line(Vector2::new(1.0, 1.0), Vector2::new(3.0, 1.0)),
+                Primitive::Line(Vector2::new(3.0, 1.0), Vector2::new(3.0, 5.0)),
+                // closing goes back to the start of the second subpath
+                Primitive::Line(Vector2::new(4.0, 5.0), Vector2::new(4.0, 6.0)),
+                Primitive::Line(Vector2::new(4.0, 6.0), Vector2::new(4.0, 5.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn smooth_cubic() {
+        let segments = [
+            Segment::MoveTo(Vector2::zeros()),
+            Segment::CurveTo(
+                Vector2::new(0.0, 1.0),
+                Vector2::new(1.0, 2.0),
+                Vector2::new(2.0, 2.0),
+            ),
+            Segment::SmoothCurve(Vector2::new(2.0, -1.0), Vector2::new(2.0, -2.0)),
+            // the previous segment is not cubic, so there is nothing to reflect
+            Segment::LineTo(Vector2::new(5.0, 0.0)),
+            Segment::SmoothCurveTo(Vector2::new(6.0, 1.0), Vector2::new(7.0, 0.0)),
+        ];
+        let primitives = resolve(&segments);
+        assert_eq!(
+            primitives[1],
+            Primitive::Cubic(
+                Vector2::new(2.0, 2.0),
+                Vector2::new(3.0, 2.0),
+                Vector2::new(4.0, 1.0),
+                Vector2::new(4.0, 0.0),
+            )
+        );
+        assert_eq!(
+            primitives[3],
+            Primitive::Cubic(
+                Vector2::new(5.0, 0.0),
+                Vector2::new(5.0, 0.0),
+                Vector2::new(6.0, 1.0),
+                Vector2::new(7.0, 0.0),
+            )
+        );
+    }
+
+    #[test]
+    fn smooth_quadratic() {
+        let segments = [
+            Segment::MoveTo(Vector2::zeros()),
+            Segment::Quad(Vector2::new(1.0, 1.0), Vector2::new(2.0, 0.0)),
+            Segment::SmoothQuadTo(Vector2::new(4.0, 0.0)),
+            Segment::SmoothQuad(Vector2::new(2.0, 0.0)),
+            // a cubic control point is not reflected into a quadratic curve
+            Segment::CurveTo(Vector2::zeros(), Vector2::zeros(), Vector2::new(8.0, 0.0)),
+            Segment::SmoothQuadTo(Vector2::new(9.0, 0.0)),
+        ];
+        let primitives = resolve(&segments);
+        assert_eq!(
+            primitives[1],
+            Primitive::Quadratic(
+                Vector2::new(2.0, 0.0),
+                Vector2::new(3.0, -1.0),
+                Vector2::new(4.0, 0.0),
+            )
+        );
+        assert_eq!(
+            primitives[2],
+            Primitive::Quadratic(
+                Vector2::new(4.0, 0.0),
+                Vector2::new(5.0, 1.0),
+                Vector2::new(6.0, 0.0),
+            )
+        );
+        assert_eq!(
+            primitives[4],
+            Primitive::Quadratic(
+                Vector2::new(8.0, 0.0),
+                Vector2::new(8.0, 0.0),
+                Vector2::new(9.0, 0.0),
+            )
+        );
+        assert_eq!(primitives[4].end(), Vector2::new(9.0, 0.0));
+    }
+
+    #[test]
+    fn directions() {
+        let from = Vector2::zeros();
+        let to = Vector2::new(2.0, 0.0);
+        let line = Primitive::Line(from, to);
+        assert_eq!(line.start_direction(), to);
+        assert_eq!(line.end_direction(), to);
+
+        // the control points coincide with the end points
+        let cubic = Primitive::Cubic(from, from, Vector2::new(1.0, 1.0), Vector2::new(1.0, 1.0));
+        assert_eq!(cubic.start_direction(), Vector2::new(1.0, 1.0));
+        assert_eq!(cubic.end_direction(), Vector2::new(1.0, 1.0));
+
+        let quadratic = Primitive::Quadratic(from, Vector2::new(1.0, 1.0), to);
+        assert_eq!(quadratic.start_direction(), Vector2::new(1.0, 1.0));
+        assert_eq!(quadratic.end_direction(), Vector2::new(1.0, -1.0));
+    }
+
+    #[test]
+    fn evaluate() {
+        let cubic = Primitive::Cubic(
+            Vector2::zeros(),
+            Vector2::new(0.0, 3.0),
+            Vector2::new(3.0, 3.0),
+            Vector2::new(3.0, 0.0),
+        );
+        assert_eq!(cubic.point(0.0), Vector2::zeros());
+        assert_eq!(cubic.point(0.5), Vector2::new(1.5, 2.25));
+        assert_eq!(cubic.point(1.0), Vector2::new(3.0, 0.0));
+        assert_eq!(cubic.derivative(0.0), Vector2::new(0.0, 9.0));
+        assert_eq!(cubic.derivative(0.5), Vector2::new(4.5, 0.0));
+
+        let quadratic = Primitive::Quadratic(
+            Vector2::zeros(),
+            Vector2::new(1.0, 2.0),
+            Vector2::new(2.0, 0.0),
+        );
+        assert_eq!(quadratic.point(0.5), Vector2::new(1.0, 1.0));
+        assert_eq!(quadratic.derivative(1.0), Vector2::new(2.0, -4.0));
+
+        let flattened = cubic.flatten();
+        assert_eq!(flattened.first(), Some(&(0.0, Vector2::zeros())));
+        assert_eq!(flattened.last(), Some(&(1.0, Vector2::new(3.0, 0.0))));
+        assert!(flattened.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(
+            Primitive::Line(Vector2::zeros(), Vector2::x())
+                .flatten()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn extrema() {
+        // the top of the arch is halfway
+        let cubic = Primitive::Cubic(
+            Vector2::zeros(),
+            Vector2::new(0.0, 3.0),
+            Vector2::new(3.0, 3.0),
+            Vector2::new(3.0, 0.0),
+        );
+        assert_eq!(cubic.extrema(), vec![Vector2::new(1.5, 2.25)]);
+
+        let quadratic = Primitive::Quadratic(
+            Vector2::zeros(),
+            Vector2::new(2.0, 2.0),
+            Vector2::new(0.0, 4.0),
+        );
+        assert_eq!(quadratic.extrema(), vec![Vector2::new(1.0, 2.0)]);
+
+        // an S-curve has one extremum per bump
+        let wave = Primitive::Cubic(
+            Vector2::zeros(),
+            Vector2::new(1.0, 2.0),
+            Vector2::new(2.0, -2.0),
+            Vector2::new(3.0, 0.0),
+        );
+        let extrema = wave.extrema();
+        assert_eq!(extrema.len(), 2);
+        assert!(extrema.iter().all(|point| point[1].abs() > 0.5));
+        assert!(Primitive::Line(Vector2::zeros(), Vector2::x())
+            .extrema()
+            .is_empty());
+    }
+
+    #[test]
+    fn arcs() {
+        let segments = [
+            Segment::MoveTo(Vector2::zeros()),
+            Segment::Arc(
+                Vector2::new(1.0, 1.0),
+                0.0,
+                false,
+                true,
+                Vector2::new(2.0, 0.0),
+            ),
+            Segment::ArcTo(Vector2::zeros(), 0.0, false, true, Vector2::new(3.0, 0.0)),
+            Segment::ArcTo(
+                Vector2::new(1.0, 1.0),
+                0.0,
+                false,
+                true,
+                Vector2::new(3.0, 0.0),
+            ),
+        ];
+        let primitives = resolve(&segments);
+        assert_eq!(primitives.len(), 2);
+        assert!(matches!(primitives[0], Primitive::Arc(_)));
+        assert!((primitives[0].end() - Vector2::new(2.0, 0.0)).norm() < 1e-5);
+        assert_eq!(
+            primitives[1],
+            Primitive::Line(Vector2::new(2.0, 0.0), Vector2::new(3.0, 0.0))
+        );
+    }
+}
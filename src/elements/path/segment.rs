@@ -1,7 +1,6 @@
 use crate::{Scalar, Vector2};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
-// TODO add Arcs
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Segment {
     MoveTo(Vector2),
@@ -14,14 +13,37 @@ pub enum Segment {
     HorizontalLine(Scalar),
     CurveTo(Vector2, Vector2, Vector2), // Bézier curves
     Curve(Vector2, Vector2, Vector2),
+    // the first control point is the reflection of the previous second control point
+    SmoothCurveTo(Vector2, Vector2),
+    SmoothCurve(Vector2, Vector2),
+    QuadTo(Vector2, Vector2), // quadratic Bézier curves
+    Quad(Vector2, Vector2),
+    // the control point is the reflection of the previous control point
+    SmoothQuadTo(Vector2),
+    SmoothQuad(Vector2),
+    // radii, rotation of the X axis in degrees, large arc flag, sweep flag, end point
+    ArcTo(Vector2, Scalar, bool, bool, Vector2), // elliptical arcs
+    Arc(Vector2, Scalar, bool, bool, Vector2),
     Close,
 }
 
 impl Segment {
     pub fn cursor(&self, previous: Vector2) -> Option<Vector2> {
         match self {
-            Self::MoveTo(xy) | Self::LineTo(xy) | Self::CurveTo(_, _, xy) => Some(*xy),
-            Self::Move(dxdy) | Self::Line(dxdy) | Self::Curve(_, _, dxdy) => Some(previous + dxdy),
+            Self::MoveTo(xy)
+            | Self::LineTo(xy)
+            | Self::CurveTo(_, _, xy)
+            | Self::SmoothCurveTo(_, xy)
+            | Self::QuadTo(_, xy)
+            | Self::SmoothQuadTo(xy)
+            | Self::ArcTo(_, _, _, _, xy) => Some(*xy),
+            Self::Move(dxdy)
+            | Self::Line(dxdy)
+            | Self::Curve(_, _, dxdy)
+            | Self::SmoothCurve(_, dxdy)
+            | Self::Quad(_, dxdy)
+            | Self::SmoothQuad(dxdy)
+            | Self::Arc(_, _, _, _, dxdy) => Some(previous + dxdy),
             Self::VerticalLineTo(y) => Some(Vector2::new(previous[0], *y)),
             Self::VerticalLine(dy) => Some(previous + Vector2::new(0.0, *dy)),
             Self::HorizontalLineTo(x) => Some(Vector2::new(*x, previous[1])),
@@ -52,6 +74,28 @@ impl Display for Segment {
                 "c {} {}, {} {}, {} {}",
                 dx1dy1[0], dx1dy1[1], dx2dy2[0], dx2dy2[1], dxdy[0], dxdy[1]
             ),
+            Self::SmoothCurveTo(x2y2, xy) => {
+                write!(f, "S {} {}, {} {}", x2y2[0], x2y2[1], xy[0], xy[1])
+            }
+            Self::SmoothCurve(dx2dy2, dxdy) => {
+                write!(f, "s {} {}, {} {}", dx2dy2[0], dx2dy2[1], dxdy[0], dxdy[1])
+            }
+            Self::QuadTo(x1y1, xy) => write!(f, "Q {} {}, {} {}", x1y1[0], x1y1[1], xy[0], xy[1]),
+            Self::Quad(dx1dy1, dxdy) => {
+                write!(f, "q {} {}, {} {}", dx1dy1[0], dx1dy1[1], dxdy[0], dxdy[1])
+            }
+            Self::SmoothQuadTo(xy) => write!(f, "T {} {}", xy[0], xy[1]),
+            Self::SmoothQuad(dxdy) => write!(f, "t {} {}", dxdy[0], dxdy[1]),
+            Self::ArcTo(radii, rotation, large_arc, sweep, xy) => write!(
+                f,
+                "A {} {} {} {} {} {} {}",
+                radii[0], radii[1], rotation, *large_arc as u8, *sweep as u8, xy[0], xy[1]
+            ),
+            Self::Arc(radii, rotation, large_arc, sweep, dxdy) => write!(
+                f,
+                "a {} {} {} {} {} {} {}",
+                radii[0], radii[1], rotation, *large_arc as u8, *sweep as u8, dxdy[0], dxdy[1]
+            ),
             Self::Close => write!(f, "Z"),
         }
     }
@@ -89,6 +133,34 @@ mod test {
                 .cursor(Vector2::new(1.0, 1.0)),
             Some(Vector2::new(11.0, 21.0))
         );
+        assert_eq!(
+            Segment::SmoothCurveTo(Vector2::zeros(), Vector2::new(10.0, 20.0))
+                .cursor(Vector2::new(1.0, 1.0)),
+            Some(Vector2::new(10.0, 20.0))
+        );
+        assert_eq!(
+            Segment::SmoothCurve(Vector2::zeros(), Vector2::new(10.0, 20.0))
+                .cursor(Vector2::new(1.0, 1.0)),
+            Some(Vector2::new(11.0, 21.0))
+        );
+        assert_eq!(
+            Segment::QuadTo(Vector2::zeros(), Vector2::new(10.0, 20.0))
+                .cursor(Vector2::new(1.0, 1.0)),
+            Some(Vector2::new(10.0, 20.0))
+        );
+        assert_eq!(
+            Segment::Quad(Vector2::zeros(), Vector2::new(10.0, 20.0))
+                .cursor(Vector2::new(1.0, 1.0)),
+            Some(Vector2::new(11.0, 21.0))
+        );
+        assert_eq!(
+            Segment::SmoothQuadTo(Vector2::new(10.0, 20.0)).cursor(Vector2::new(1.0, 1.0)),
+            Some(Vector2::new(10.0, 20.0))
+        );
+        assert_eq!(
+            Segment::SmoothQuad(Vector2::new(10.0, 20.0)).cursor(Vector2::new(1.0, 1.0)),
+            Some(Vector2::new(11.0, 21.0))
+        );
         assert_eq!(
             Segment::VerticalLineTo(20.0).cursor(Vector2::new(1.0, 1.0)),
             Some(Vector2::new(1.0, 20.0))
@@ -105,6 +177,28 @@ mod test {
             Segment::HorizontalLine(20.0).cursor(Vector2::new(1.0, 1.0)),
             Some(Vector2::new(21.0, 1.0))
         );
+        assert_eq!(
+            Segment::ArcTo(
+                Vector2::new(5.0, 3.0),
+                30.0,
+                true,
+                false,
+                Vector2::new(10.0, 20.0)
+            )
+            .cursor(Vector2::new(1.0, 1.0)),
+            Some(Vector2::new(10.0, 20.0))
+        );
+        assert_eq!(
+            Segment::Arc(
+                Vector2::new(5.0, 3.0),
+                30.0,
+                true,
+                false,
+                Vector2::new(10.0, 20.0)
+            )
+            .cursor(Vector2::new(1.0, 1.0)),
+            Some(Vector2::new(11.0, 21.0))
+        );
         assert!(Segment::Close.cursor(Vector2::zeros()).is_none());
     }
 
@@ -130,6 +224,20 @@ mod test {
             Segment::Curve(c1, c2, rel).to_string(),
             "c -10 20, 1 0, 1.5 -2.5"
         );
+        assert_eq!(Segment::SmoothCurveTo(c2, abs).to_string(), "S 1 0, 1 2");
+        assert_eq!(Segment::SmoothCurve(c2, rel).to_string(), "s 1 0, 1.5 -2.5");
+        assert_eq!(Segment::QuadTo(c1, abs).to_string(), "Q -10 20, 1 2");
+        assert_eq!(Segment::Quad(c1, rel).to_string(), "q -10 20, 1.5 -2.5");
+        assert_eq!(Segment::SmoothQuadTo(abs).to_string(), "T 1 2");
+        assert_eq!(Segment::SmoothQuad(rel).to_string(), "t 1.5 -2.5");
+        assert_eq!(
+            Segment::ArcTo(c1, 45.0, true, false, abs).to_string(),
+            "A -10 20 45 1 0 1 2"
+        );
+        assert_eq!(
+            Segment::Arc(c2, -30.0, false, true, rel).to_string(),
+            "a 1 0 -30 0 1 1.5 -2.5"
+        );
         assert_eq!(Segment::Close.to_string(), "Z");
     }
 }
@@ -0,0 +1,250 @@
+use super::segment::Segment;
+use super::Path;
+use crate::transform::Transform;
+use crate::{Scalar, Vector2};
+
+impl Path {
+    /// Rewrites every coordinate of the path, so the result needs no SVG transform. Horizontal
+    /// and vertical lines that do not stay horizontal or vertical become general lines, and arcs
+    /// get the radii and rotation of the transformed ellipse. The transformation is either a
+    /// `Transform` or an affine `Matrix3` acting on homogeneous coordinates.
+    pub fn transform(&self, transform: impl Into<Transform>) -> Path {
+        let transform = &transform.into();
+        let linear = transform.linear();
+        // whether the axes keep their direction, up to scaling
+        let horizontal = linear[(1, 0)] == 0.0;
+        let vertical = linear[(0, 1)] == 0.0;
+        let point = |xy: Vector2| transform.apply(xy);
+        let vector = |dxdy: Vector2| transform.apply_vector(dxdy);
+
+        let mut current = Vector2::zeros();
+        let mut subpath_start = current;
+        let segments = self
+            .0
+            .iter()
+            .map(|segment| {
+                let previous = current;
+                current = match segment {
+                    // the pen goes back to where the subpath started
+                    Segment::Close => subpath_start,
+                    // NOTE only Close has no cursor
+                    _ => segment.cursor(previous).unwrap(),
+                };
+                if let Segment::MoveTo(_) | Segment::Move(_) = segment {
+                    subpath_start = current;
+                }
+                match *segment {
+                    Segment::MoveTo(xy) => Segment::MoveTo(point(xy)),
+                    Segment::Move(dxdy) => Segment::Move(vector(dxdy)),
+                    Segment::LineTo(xy) => Segment::LineTo(point(xy)),
+                    Segment::Line(dxdy) => Segment::Line(vector(dxdy)),
+                    Segment::HorizontalLineTo(_) if horizontal => {
+                        Segment::HorizontalLineTo(point(current)[0])
+                    }
+                    Segment::VerticalLineTo(_) if vertical => {
+                        Segment::VerticalLineTo(point(current)[1])
+                    }
+                    Segment::HorizontalLineTo(_) | Segment::VerticalLineTo(_) => {
+                        Segment::LineTo(point(current))
+                    }
+                    Segment::HorizontalLine(dx) if horizontal => {
+                        Segment::HorizontalLine(vector(Vector2::new(dx, 0.0))[0])
+                    }
+                    Segment::VerticalLine(dy) if vertical => {
+                        Segment::VerticalLine(vector(Vector2::new(0.0, dy))[1])
+                    }
+                    Segment::HorizontalLine(_) | Segment::VerticalLine(_) => {
+                        Segment::Line(vector(current - previous))
+                    }
+                    Segment::CurveTo(x1y1, x2y2, xy) => {
+                        Segment::CurveTo(point(x1y1), point(x2y2), point(xy))
+                    }
+                    Segment::Curve(dx1dy1, dx2dy2, dxdy) => {
+                        Segment::Curve(vector(dx1dy1), vector(dx2dy2), vector(dxdy))
+                    }
+                    Segment::SmoothCurveTo(x2y2, xy) => {
+                        Segment::SmoothCurveTo(point(x2y2), point(xy))
+                    }
+                    Segment::SmoothCurve(dx2dy2, dxdy) => {
+                        Segment::SmoothCurve(vector(dx2dy2), vector(dxdy))
+                    }
+                    Segment::QuadTo(x1y1, xy) => Segment::QuadTo(point(x1y1), point(xy)),
+                    Segment::Quad(dx1dy1, dxdy) => Segment::Quad(vector(dx1dy1), vector(dxdy)),
+                    Segment::SmoothQuadTo(xy) => Segment::SmoothQuadTo(point(xy)),
+                    Segment::SmoothQuad(dxdy) => Segment::SmoothQuad(vector(dxdy)),
+                    Segment::ArcTo(radii, rotation, large_arc, sweep, xy) => {
+                        let (radii, rotation, sweep) = ellipse(transform, radii, rotation, sweep);
+                        Segment::ArcTo(radii, rotation, large_arc, sweep, point(xy))
+                    }
+                    Segment::Arc(radii, rotation, large_arc, sweep, dxdy) => {
+                        let (radii, rotation, sweep) = ellipse(transform, radii, rotation, sweep);
+                        Segment::Arc(radii, rotation, large_arc, sweep, vector(dxdy))
+                    }
+                    Segment::Close => Segment::Close,
+                }
+            })
+            .collect();
+        Path(segments)
+    }
+
+    pub fn translate(&self, delta: Vector2) -> Path {
+        self.transform(Transform::translation(delta))
+    }
+
+    /// See [`Transform::rotation`].
+    pub fn rotate(&self, angle: Scalar, around: Vector2) -> Path {
+        self.transform(Transform::rotation(angle, around))
+    }
+
+    /// See [`Transform::scaling`].
+    pub fn scale(&self, factors: Vector2) -> Path {
+        self.transform(Transform::scaling(factors))
+    }
+
+    /// See [`Transform::mirror`].
+    pub fn mirror(&self, through: Vector2, angle: Scalar) -> Path {
+        self.transform(Transform::mirror(through, angle))
+    }
+}
+
+// Radii, rotation in degrees and sweep flag of the transformed ellipse of an arc. The ellipse is
+// the image of the unit circle under `linear * rotation * diag(radii)`, whose singular value
+// decomposition gives its axes.
+fn ellipse(
+    transform: &Transform,
+    radii: Vector2,
+    rotation: Scalar,
+    sweep: bool,
+) -> (Vector2, Scalar, bool) {
+    let (s, c) = rotation.to_radians().sin_cos();
+    let shape = transform.linear()
+        * nalgebra::Matrix2::new(c * radii[0], -s * radii[1], s * radii[0], c * radii[1]);
+    let (a, b, c, d) = (shape[(0, 0)], shape[(0, 1)], shape[(1, 0)], shape[(1, 1)]);
+    // closed form of the decomposition into rotation(β) * diag(p + q, p - q) * rotation(γ)
+    let e = (a + d) / 2.0;
+    let f = (a - d) / 2.0;
+    let g = (c + b) / 2.0;
+    let h = (c - b) / 2.0;
+    let p = e.hypot(h);
+    let q = f.hypot(g);
+    let beta = (g.atan2(f) + h.atan2(e)) / 2.0;
+    let radii = Vector2::new(p + q, (p - q).abs());
+    // a mirror turns counterclockwise arcs into clockwise ones
+    (radii, beta.to_degrees(), sweep != transform.is_reflection())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::close;
+    use crate::elements::PathBuilder;
+
+    #[test]
+    fn lines() {
+        let path = PathBuilder::start(Vector2::new(1.0, 0.0))
+            .hline(2.0)
+            .vline_to(1.0)
+            .line(Vector2::new(-1.0, 1.0))
+            .close();
+
+        let moved = path.translate(Vector2::new(1.0, 1.0));
+        assert_eq!(
+            moved.segments(),
+            &[
+                Segment::MoveTo(Vector2::new(2.0, 1.0)),
+                Segment::HorizontalLine(2.0),
+                Segment::VerticalLineTo(2.0),
+                Segment::Line(Vector2::new(-1.0, 1.0)),
+                Segment::Close,
+            ]
+        );
+
+        // under rotation axis-aligned lines become general ones
+        let rotated = path.rotate(90.0, Vector2::zeros());
+        assert!(matches!(rotated.segments()[1], Segment::Line(_)));
+        assert!(matches!(rotated.segments()[2], Segment::LineTo(_)));
+        for i in 0..path.len() {
+            let expected = Transform::rotation(90.0, Vector2::zeros()).apply(path.cursor(i));
+            assert!(close(rotated.cursor(i), expected));
+        }
+
+        let mirrored = path.mirror(Vector2::zeros(), 90.0);
+        assert_eq!(mirrored.cursor(2), Vector2::new(-3.0, 1.0));
+        assert_eq!(mirrored.segments()[1], Segment::HorizontalLine(-2.0));
+
+        // lines after a close start at the beginning of the subpath
+        let closed = Path(vec![
+            Segment::MoveTo(Vector2::zeros()),
+            Segment::LineTo(Vector2::new(4.0, 0.0)),
+            Segment::LineTo(Vector2::new(4.0, 4.0)),
+            Segment::Close,
+            Segment::HorizontalLineTo(2.0),
+        ]);
+        let rotated = closed.rotate(90.0, Vector2::zeros());
+        match rotated.segments()[4] {
+            Segment::LineTo(xy) => assert!(close(xy, Vector2::new(0.0, 2.0))),
+            _ => panic!("expected a line"),
+        }
+        let skewed = closed.transform(Transform::skew(45.0, 0.0));
+        assert_eq!(skewed.segments()[4], Segment::HorizontalLineTo(2.0));
+        let matrix = nalgebra::Matrix3::new(1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        assert_eq!(closed.transform(matrix).segments(), skewed.segments());
+    }
+
+    #[test]
+    fn arcs() {
+        // quarter of the unit circle from east to north
+        let path = PathBuilder::start(Vector2::new(1.0, 0.0))
+            .arc(0.0, 90.0, 1.0)
+            .end();
+
+        let scaled = path.scale(Vector2::new(3.0, 2.0));
+        match scaled.segments()[1] {
+            Segment::Arc(radii, rotation, false, true, dxdy) => {
+                assert!(close(radii, Vector2::new(3.0, 2.0)));
+                assert!(rotation.abs() < 1e-4);
+                assert!(close(dxdy, Vector2::new(-3.0, 2.0)));
+            }
+            _ => panic!("expected an arc"),
+        }
+        // the drawn arc is on the transformed ellipse
+        let middle = scaled.point_at(0.5);
+        assert!(((middle[0] / 3.0).powi(2) + (middle[1] / 2.0).powi(2) - 1.0).abs() < 1e-4);
+
+        // a mirror reverses the direction of the sweep
+        let mirrored = path.mirror(Vector2::zeros(), 0.0);
+        match mirrored.segments()[1] {
+            Segment::Arc(_, _, _, sweep, dxdy) => {
+                assert!(!sweep);
+                assert!(close(dxdy, Vector2::new(-1.0, -1.0)));
+            }
+            _ => panic!("expected an arc"),
+        }
+        let diagonal = 0.5_f32.sqrt();
+        assert!(close(
+            mirrored.point_at(0.5),
+            Vector2::new(diagonal, -diagonal)
+        ));
+
+        // a rotated ellipse
+        let path = PathBuilder::start(Vector2::zeros())
+            .elliptical_arc_to(
+                Vector2::new(2.0, 1.0),
+                0.0,
+                false,
+                true,
+                Vector2::new(4.0, 0.0),
+            )
+            .end();
+        let rotated = path.rotate(30.0, Vector2::zeros());
+        match rotated.segments()[1] {
+            Segment::ArcTo(radii, rotation, _, _, _) => {
+                assert!(close(radii, Vector2::new(2.0, 1.0)));
+                assert!((rotation - 30.0).abs() < 1e-3);
+            }
+            _ => panic!("expected an arc"),
+        }
+        let expected = Transform::rotation(30.0, Vector2::zeros()).apply(path.point_at(0.5));
+        assert!(close(rotated.point_at(0.5), expected));
+    }
+}
@@ -0,0 +1,221 @@
+use super::{Element, ReprT};
+use crate::anchor::{anchor_polygon, anchor_transformed, polar_coordinates, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
+use crate::svgutils::keys;
+use crate::{Scalar, Vector2};
+
+/// Closed outline through a list of points.
+pub struct Polygon;
+
+/// Open line through a list of points. Its anchors are placed as if it was closed.
+pub struct Polyline;
+
+impl ReprT for Polygon {
+    type Repr = crate::style::Stroke;
+}
+
+impl ReprT for Polyline {
+    type Repr = crate::style::Stroke;
+}
+
+impl Element<Polygon> {
+    pub fn points(self, points: &[Vector2]) -> Self {
+        self.insert(keys::POINTS, format_points(points));
+        self
+    }
+
+    pub fn vertices(&self) -> Vec<Vector2> {
+        parse_points(&self.get::<String>(keys::POINTS))
+    }
+}
+
+impl Element<Polyline> {
+    pub fn points(self, points: &[Vector2]) -> Self {
+        self.insert(keys::POINTS, format_points(points));
+        self
+    }
+
+    pub fn vertices(&self) -> Vec<Vector2> {
+        parse_points(&self.get::<String>(keys::POINTS))
+    }
+}
+
+impl Bounded for Element<Polygon> {
+    fn bounding_box(&self) -> BoundingBox {
+        let transform = self.transformation();
+        let vertices: Vec<Vector2> = self
+            .vertices()
+            .into_iter()
+            .map(|vertex| transform.apply(vertex))
+            .collect();
+        bounding_box(&vertices)
+    }
+}
+
+impl AnchorT for Element<Polygon> {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        let vertices = self.vertices();
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            anchor_polygon(anchor, &vertices)
+        })
+    }
+}
+
+impl Bounded for Element<Polyline> {
+    fn bounding_box(&self) -> BoundingBox {
+        let transform = self.transformation();
+        let vertices: Vec<Vector2> = self
+            .vertices()
+            .into_iter()
+            .map(|vertex| transform.apply(vertex))
+            .collect();
+        bounding_box(&vertices)
+    }
+}
+
+impl AnchorT for Element<Polyline> {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        let vertices = self.vertices();
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            anchor_polygon(anchor, &vertices)
+        })
+    }
+}
+
+/// Corners of a regular polygon. Like in TikZ, odd polygons have a corner at the top and even
+/// ones a flat top edge.
+pub fn regular_polygon(center: Vector2, radius: Scalar, sides: usize) -> Vec<Vector2> {
+    let step = 360.0 / sides as Scalar;
+    let start = if sides & 1 == 0 {
+        90.0 + step / 2.0
+    } else {
+        90.0
+    };
+    (0..sides)
+        .map(|i| center + polar_coordinates(radius, start + i as Scalar * step))
+        .collect()
+}
+
+/// Corners of a star with its first tip at the top, alternating between the outer and the
+/// inner radius.
+pub fn star(
+    center: Vector2,
+    outer_radius: Scalar,
+    inner_radius: Scalar,
+    tips: usize,
+) -> Vec<Vector2> {
+    let step = 180.0 / tips as Scalar;
+    (0..2 * tips)
+        .map(|i| {
+            let radius = if i & 1 == 0 {
+                outer_radius
+            } else {
+                inner_radius
+            };
+            center + polar_coordinates(radius, 90.0 + i as Scalar * step)
+        })
+        .collect()
+}
+
+/// Corners of a diamond, starting from the east going counterclockwise.
+pub fn diamond(center: Vector2, width: Scalar, height: Scalar) -> Vec<Vector2> {
+    let half = Vector2::new(width, height) / 2.0;
+    vec![
+        center + Vector2::new(half[0], 0.0),
+        center + Vector2::new(0.0, half[1]),
+        center - Vector2::new(half[0], 0.0),
+        center - Vector2::new(0.0, half[1]),
+    ]
+}
+
+pub(crate) fn format_points(points: &[Vector2]) -> String {
+    points
+        .iter()
+        .map(|point| format!("{},{}", point[0], point[1]))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+pub(crate) fn parse_points(points: &str) -> Vec<Vector2> {
+    points
+        .split_whitespace()
+        .filter_map(|point| {
+            let (x, y) = point.split_once(',')?;
+            Some(Vector2::new(x.parse().ok()?, y.parse().ok()?))
+        })
+        .collect()
+}
+
+fn bounding_box(points: &[Vector2]) -> BoundingBox {
+    BoundingBox::from_points(points.iter().copied())
+        .unwrap_or(BoundingBox::new(Vector2::zeros(), Vector2::zeros()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::close;
+    use crate::svgutils::raw;
+    use std::cell::RefCell;
+    use std::ops::Deref;
+    use std::rc::Rc;
+
+    #[test]
+    fn create_and_modify() {
+        let elem = Rc::new(RefCell::new(raw::Polygon::new().deref().clone()));
+        let points = diamond(Vector2::new(1.0, 1.0), 4.0, 2.0);
+        let polygon = Element::<Polygon>::new(Rc::clone(&elem)).points(&points);
+
+        assert_eq!(
+            elem.borrow()
+                .get_attributes()
+                .get(keys::POINTS)
+                .unwrap()
+                .deref(),
+            "3,1 1,2 -1,1 1,0"
+        );
+        assert_eq!(polygon.vertices(), points);
+        let bbox = polygon.bounding_box();
+        assert_eq!(bbox.min, Vector2::new(-1.0, 0.0));
+        assert_eq!(bbox.max, Vector2::new(3.0, 2.0));
+
+        assert!(close(polygon.origin(), Vector2::new(1.0, 1.0)));
+        assert!(close(polygon.north(), Vector2::new(1.0, 2.0)));
+        // the diagonal anchors are on the edges of the diamond
+        assert!(close(
+            polygon.northeast(),
+            Vector2::new(5.0 / 3.0, 5.0 / 3.0)
+        ));
+    }
+
+    #[test]
+    fn polyline() {
+        let elem = Rc::new(RefCell::new(raw::Polyline::new().deref().clone()));
+        let points = [
+            Vector2::zeros(),
+            Vector2::new(4.0, 0.0),
+            Vector2::new(4.0, 4.0),
+        ];
+        let polyline = Element::<Polyline>::new(Rc::clone(&elem)).points(&points);
+        assert_eq!(polyline.vertices(), points);
+        assert_eq!(polyline.bounding_box().max, Vector2::new(4.0, 4.0));
+        assert!(close(polyline.east(), Vector2::new(4.0, 4.0 / 3.0)));
+    }
+
+    #[test]
+    fn shapes() {
+        let triangle = regular_polygon(Vector2::zeros(), 2.0, 3);
+        assert!(close(triangle[0], Vector2::new(0.0, 2.0)));
+        assert!(close(triangle[1], polar_coordinates(2.0, 210.0)));
+
+        // even polygons have a flat top
+        let square = regular_polygon(Vector2::zeros(), 2.0_f32.sqrt(), 4);
+        assert!(close(square[0], Vector2::new(-1.0, 1.0)));
+        assert!(close(square[3], Vector2::new(1.0, 1.0)));
+
+        let star = star(Vector2::new(1.0, 0.0), 2.0, 1.0, 5);
+        assert_eq!(star.len(), 10);
+        assert!(close(star[0], Vector2::new(1.0, 2.0)));
+        assert!(close(star[5], Vector2::new(1.0, -1.0)));
+    }
+}
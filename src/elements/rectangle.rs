@@ -1,5 +1,6 @@
 use super::{Element, ReprT};
-use crate::anchor::{anchor_rectangle, Anchor, AnchorT};
+use crate::anchor::{anchor_rounded_rectangle, anchor_transformed, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
 use crate::svgutils::keys;
 use crate::{Scalar, Vector2};
 
@@ -13,6 +14,7 @@ struct Geometry {
     origin: Vector2,
     height: Scalar,
     width: Scalar,
+    corner_radius: Scalar,
 }
 
 impl Element<Rectangle> {
@@ -52,19 +54,32 @@ impl Element<Rectangle> {
             origin: Vector2::new(x + width / 2.0, y + height / 2.0),
             height,
             width,
+            corner_radius: self.get(keys::CORNER_RADIUS),
         }
     }
 }
 
+impl Bounded for Element<Rectangle> {
+    fn bounding_box(&self) -> BoundingBox {
+        let geometry = self.geometry();
+        let half = Vector2::new(geometry.width, geometry.height) / 2.0;
+        BoundingBox::new(geometry.origin - half, geometry.origin + half)
+            .transform(&self.transformation())
+    }
+}
+
 impl AnchorT for Element<Rectangle> {
     fn anchor(&self, anchor: Anchor) -> Vector2 {
         let geometry = self.geometry();
-        anchor_rectangle(
-            anchor,
-            geometry.origin,
-            geometry.width / 2.0,
-            geometry.height / 2.0,
-        )
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            anchor_rounded_rectangle(
+                anchor,
+                geometry.origin,
+                geometry.width / 2.0,
+                geometry.height / 2.0,
+                geometry.corner_radius,
+            )
+        })
     }
 }
 
@@ -107,6 +122,10 @@ mod test {
         assert_eq!(geometry.width, 1.5);
         assert_eq!(geometry.origin, Vector2::new(10.0, 20.0));
 
+        let bbox = other_rect.bounding_box();
+        assert_eq!(bbox.min, Vector2::new(9.25, 19.0));
+        assert_eq!(bbox.max, Vector2::new(10.75, 21.0));
+
         // check rounded corners
         assert_eq!(
             other_elem
@@ -117,5 +136,44 @@ mod test {
                 .deref(),
             "0.5"
         );
+        assert_eq!(rect.geometry().corner_radius, 0.0);
+        assert_eq!(other_rect.geometry().corner_radius, 0.5);
+
+        // border anchors follow the rounded corners
+        let corner = other_rect.border(45.0) - Vector2::new(10.25, 20.5);
+        assert!((corner.norm() - 0.5).abs() < 1e-5);
+        assert!((rect.border(0.0) - Vector2::new(0.75, 0.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn transformed() {
+        let elem = Rc::new(RefCell::new(raw::Rectangle::new().deref().clone()));
+        let rect = Element::<Rectangle>::new(elem)
+            .width(4.0)
+            .height(2.0)
+            .at(Vector2::new(2.0, 0.0))
+            .rotate(90.0, Vector2::zeros());
+
+        // compass anchors turn with the rectangle
+        assert!((rect.origin() - Vector2::new(0.0, 2.0)).norm() < 1e-5);
+        assert!((rect.east() - Vector2::new(0.0, 4.0)).norm() < 1e-5);
+        assert!((rect.north() - Vector2::new(-1.0, 2.0)).norm() < 1e-5);
+        // border angles stay in the coordinates of the document
+        assert!((rect.border(90.0) - Vector2::new(0.0, 4.0)).norm() < 1e-5);
+        assert!((rect.border(0.0) - Vector2::new(1.0, 2.0)).norm() < 1e-5);
+
+        let bbox = rect.bounding_box();
+        assert!((bbox.min - Vector2::new(-1.0, 0.0)).norm() < 1e-5);
+        assert!((bbox.max - Vector2::new(1.0, 4.0)).norm() < 1e-5);
+
+        // transformations add up
+        let rect = rect
+            .translate(Vector2::new(1.0, 0.0))
+            .scale(Vector2::repeat(2.0));
+        assert!((rect.origin() - Vector2::new(2.0, 4.0)).norm() < 1e-5);
+        let inverse = rect.transformation().inverse().unwrap();
+        let rect = rect.transform(&inverse);
+        assert!(rect.transformation().is_identity());
+        assert!(rect.get::<String>(keys::TRANSFORM).is_empty());
     }
 }
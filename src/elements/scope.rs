@@ -0,0 +1,230 @@
+use super::{geometry, ElemRef, Element, Instance, ReprT};
+use crate::anchor::{anchor_transformed, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
+use crate::style::{Stroke, Style};
+use crate::transform::Transform;
+use crate::{Scalar, Vector2};
+use std::cell::Cell;
+use std::rc::Rc;
+
+pub struct Group;
+
+impl ReprT for Group {
+    type Repr = Stroke;
+}
+
+/// Something drawn by a document: a single element, a scope with its own children or an
+/// instance of a symbol.
+#[derive(Clone)]
+pub(crate) enum Item {
+    Element(ElemRef),
+    Scope(Scope),
+    Instance(Instance),
+}
+
+impl Item {
+    /// Geometric extent in the coordinates of the parent, `None` if nothing is drawn.
+    pub(crate) fn bounding_box(&self) -> Option<BoundingBox> {
+        match self {
+            Self::Element(elem) => geometry(elem),
+            Self::Scope(scope) => scope.enclose(Item::bounding_box),
+            Self::Instance(instance) => instance.enclose(Item::bounding_box),
+        }
+    }
+}
+
+/// Group of elements, like the `scope` environment of TikZ, see `Document::scope`. The style of
+/// the scope is inherited by its children unless they set their own, and its transformation and
+/// clip apply to all of them. Handles of the children work in the coordinates of the scope.
+#[derive(Clone)]
+pub struct Scope {
+    group: Element<Group>,
+    items: Rc<Vec<Item>>,
+    // shared by the clones, so that the document sees the region set through any of them
+    clip: Rc<Cell<Option<BoundingBox>>>,
+}
+
+impl Scope {
+    pub(crate) fn new(group: Element<Group>, items: Vec<Item>) -> Self {
+        Self {
+            group,
+            items: Rc::new(items),
+            clip: Rc::new(Cell::new(None)),
+        }
+    }
+
+    pub fn with_style(self, style: &Style<Stroke>) -> Self {
+        let group = self.group.with_style(style);
+        Self { group, ..self }
+    }
+
+    /// Only the part of the children inside `region` is drawn, the region is given in the
+    /// coordinates of the scope.
+    pub fn clip(self, region: BoundingBox) -> Self {
+        self.clip.set(Some(region));
+        self
+    }
+
+    /// Moves the scope so that the center of its children ends up at `origin`.
+    pub fn at(self, origin: Vector2) -> Self {
+        let delta = origin - self.origin();
+        self.translate(delta)
+    }
+
+    /// Applies `transform` after the current transformation of the scope.
+    pub fn transform(self, transform: &Transform) -> Self {
+        let group = self.group.transform(transform);
+        Self { group, ..self }
+    }
+
+    pub fn translate(self, delta: Vector2) -> Self {
+        self.transform(&Transform::translation(delta))
+    }
+
+    /// See [`Transform::rotation`].
+    pub fn rotate(self, angle: Scalar, around: Vector2) -> Self {
+        self.transform(&Transform::rotation(angle, around))
+    }
+
+    /// See [`Transform::scaling`].
+    pub fn scale(self, factors: Vector2) -> Self {
+        self.transform(&Transform::scaling(factors))
+    }
+
+    pub fn transformation(&self) -> Transform {
+        self.group.transformation()
+    }
+
+    pub fn clip_region(&self) -> Option<BoundingBox> {
+        self.clip.get()
+    }
+
+    pub(crate) fn group(&self) -> &Element<Group> {
+        &self.group
+    }
+
+    pub(crate) fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// Union of the boxes of the children in the coordinates of the scope, cut to the clip
+    /// region.
+    fn local_box<F: Fn(&Item) -> Option<BoundingBox>>(&self, child: F) -> Option<BoundingBox> {
+        let bbox = self
+            .items
+            .iter()
+            .filter_map(child)
+            .reduce(|acc, x| acc.union(&x))?;
+        match self.clip_region() {
+            Some(region) => bbox.intersection(&region),
+            None => Some(bbox),
+        }
+    }
+
+    /// Box around the boxes of the children given by `child`, in the coordinates of the parent.
+    pub(crate) fn enclose<F: Fn(&Item) -> Option<BoundingBox>>(
+        &self,
+        child: F,
+    ) -> Option<BoundingBox> {
+        Some(self.local_box(child)?.transform(&self.transformation()))
+    }
+}
+
+/// An empty scope is a point at the origin of its coordinate system.
+impl Bounded for Scope {
+    fn bounding_box(&self) -> BoundingBox {
+        self.enclose(Item::bounding_box)
+            .unwrap_or_else(|| BoundingBox::new(Vector2::zeros(), Vector2::zeros()))
+    }
+}
+
+/// The anchors of the bounding box of the children, which turns with the scope.
+impl AnchorT for Scope {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        let local = self
+            .local_box(Item::bounding_box)
+            .unwrap_or_else(|| BoundingBox::new(Vector2::zeros(), Vector2::zeros()));
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            local.anchor(anchor)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::close;
+    use crate::elements::Document;
+    use crate::style::Color;
+    use crate::svgutils::keys;
+
+    #[test]
+    fn anchors() {
+        let mut document = Document::new();
+        let scope = document.scope(|document| {
+            document.circle().radius(1.0);
+            document
+                .rectangle()
+                .width(2.0)
+                .height(4.0)
+                .at(Vector2::new(4.0, 0.0));
+        });
+        assert_eq!(
+            scope.bounding_box(),
+            BoundingBox::new(Vector2::new(-1.0, -2.0), Vector2::new(5.0, 2.0))
+        );
+        assert_eq!(scope.origin(), Vector2::new(2.0, 0.0));
+        assert_eq!(scope.east(), Vector2::new(5.0, 0.0));
+
+        // the whole scope is moved, the children keep their own coordinates
+        let scope = scope.at(Vector2::new(10.0, 10.0));
+        assert_eq!(scope.origin(), Vector2::new(10.0, 10.0));
+        assert_eq!(scope.northwest(), Vector2::new(7.0, 12.0));
+        assert_eq!(document.bounding_box(), Some(scope.bounding_box()));
+
+        let center = scope.origin();
+        let scope = scope.rotate(90.0, center);
+        assert!(close(scope.east(), Vector2::new(10.0, 13.0)));
+        assert!(close(scope.north(), Vector2::new(8.0, 10.0)));
+
+        // only the clipped part counts, the region is in the coordinates of the scope
+        let scope = scope.clip(BoundingBox::new(Vector2::new(-1.0, -1.0), Vector2::zeros()));
+        assert!(close(scope.origin(), Vector2::new(10.5, 7.5)));
+        let empty = document.scope(|_| {});
+        assert_eq!(empty.origin(), Vector2::zeros());
+    }
+
+    #[test]
+    fn nested() {
+        let mut document = Document::new();
+        document.border(0.0);
+        let outer = document.scope(|document| {
+            let inner = document.scope(|document| {
+                document.circle().radius(1.0);
+            });
+            inner.translate(Vector2::new(1.0, 0.0));
+            document
+                .line()
+                .start(Vector2::zeros())
+                .end(Vector2::new(0.0, 4.0))
+                .with_style(&Style::new().stroke(Stroke::new().color(Color::Red)));
+        });
+        let outer = outer
+            .scale(Vector2::new(2.0, 1.0))
+            .with_style(&Style::new().stroke(Stroke::new().color(Color::Black).width(2.0)));
+        assert_eq!(
+            outer.bounding_box(),
+            BoundingBox::new(Vector2::new(0.0, -1.0), Vector2::new(4.0, 4.0))
+        );
+        // the line keeps the width of its own stroke while the circle inherits the one of the
+        // scope, and both are scaled with the scope
+        assert_eq!(
+            document.bounding_box(),
+            Some(BoundingBox::new(
+                Vector2::new(-2.0, -2.0),
+                Vector2::new(6.0, 4.5)
+            ))
+        );
+        assert_eq!(outer.group().get::<Scalar>(keys::STROKE_WIDTH), 2.0);
+    }
+}
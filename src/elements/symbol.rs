@@ -0,0 +1,208 @@
+use super::{Element, Item};
+use crate::anchor::{anchor_transformed, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
+use crate::transform::Transform;
+use crate::{Scalar, Vector2};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const ID_PREFIX: &str = "symbol-";
+
+pub struct Use;
+
+/// Drawing that is defined once in the definitions of a document and drawn by any number of
+/// instances, see `Document::symbol`. Its elements are given around the origin of the symbol.
+#[derive(Clone)]
+pub struct Symbol {
+    index: usize,
+    items: Rc<Vec<Item>>,
+    anchors: Rc<RefCell<Vec<(Anchor, Vector2)>>>,
+}
+
+impl Symbol {
+    pub(crate) fn new(index: usize, items: Vec<Item>) -> Self {
+        Self {
+            index,
+            items: Rc::new(items),
+            anchors: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Declares where `anchor` of every instance is, in the coordinates of the symbol. Anchors
+    /// that are not declared are the ones of the bounding box of the symbol.
+    pub fn anchor(self, anchor: Anchor, at: Vector2) -> Self {
+        {
+            let mut anchors = self.anchors.borrow_mut();
+            anchors.retain(|(declared, _)| *declared != anchor);
+            anchors.push((anchor, at));
+        }
+        self
+    }
+
+    pub fn id(&self) -> String {
+        format!("{}{}", ID_PREFIX, self.index)
+    }
+
+    pub(crate) fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    fn local_box<F: Fn(&Item) -> Option<BoundingBox>>(&self, child: F) -> Option<BoundingBox> {
+        self.items
+            .iter()
+            .filter_map(child)
+            .reduce(|acc, x| acc.union(&x))
+    }
+
+    fn local_anchor(&self, anchor: Anchor) -> Vector2 {
+        let declared = self
+            .anchors
+            .borrow()
+            .iter()
+            .find(|(declared, _)| *declared == anchor)
+            .map(|(_, at)| *at);
+        declared.unwrap_or_else(|| self.bounding_box().anchor(anchor))
+    }
+}
+
+/// An empty symbol is a point at its origin.
+impl Bounded for Symbol {
+    fn bounding_box(&self) -> BoundingBox {
+        self.local_box(Item::bounding_box)
+            .unwrap_or_else(|| BoundingBox::new(Vector2::zeros(), Vector2::zeros()))
+    }
+}
+
+/// Drawing of a symbol, scaled first, then rotated around the origin of the symbol and finally
+/// moved to its position.
+#[derive(Clone)]
+pub struct Instance {
+    element: Element<Use>,
+    symbol: Symbol,
+    position: Vector2,
+    rotation: Scalar,
+    scale: Vector2,
+}
+
+impl Instance {
+    pub(crate) fn new(element: Element<Use>, symbol: Symbol) -> Self {
+        Self {
+            element,
+            symbol,
+            position: Vector2::zeros(),
+            rotation: 0.0,
+            scale: Vector2::repeat(1.0),
+        }
+    }
+
+    /// Where the origin of the symbol ends up.
+    pub fn at(self, position: Vector2) -> Self {
+        Self { position, ..self }.update()
+    }
+
+    /// Counterclockwise rotation around the origin of the symbol, in degrees.
+    pub fn rotation(self, rotation: Scalar) -> Self {
+        Self { rotation, ..self }.update()
+    }
+
+    pub fn scale(self, scale: Vector2) -> Self {
+        Self { scale, ..self }.update()
+    }
+
+    pub fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    pub fn transformation(&self) -> Transform {
+        self.element.transformation()
+    }
+
+    pub(crate) fn element(&self) -> &Element<Use> {
+        &self.element
+    }
+
+    /// Box around the boxes of the elements of the symbol given by `child`, in the coordinates
+    /// the instance is drawn in.
+    pub(crate) fn enclose<F: Fn(&Item) -> Option<BoundingBox>>(
+        &self,
+        child: F,
+    ) -> Option<BoundingBox> {
+        Some(
+            self.symbol
+                .local_box(child)?
+                .transform(&self.transformation()),
+        )
+    }
+
+    fn update(self) -> Self {
+        let transform = Transform::scaling(self.scale)
+            .then(&Transform::rotation(self.rotation, Vector2::zeros()))
+            .then(&Transform::translation(self.position));
+        self.element.write_transform(&transform, "");
+        self
+    }
+}
+
+impl Bounded for Instance {
+    fn bounding_box(&self) -> BoundingBox {
+        self.symbol.bounding_box().transform(&self.transformation())
+    }
+}
+
+impl AnchorT for Instance {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            self.symbol.local_anchor(anchor)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::close;
+    use crate::elements::Document;
+
+    #[test]
+    fn anchors() {
+        let mut document = Document::new();
+        // a resistor with its terminals at the ends of the leads
+        let resistor = document
+            .symbol(|document| {
+                document.rectangle().width(4.0).height(2.0);
+                document
+                    .line()
+                    .start(Vector2::new(-4.0, 0.0))
+                    .end(Vector2::new(-2.0, 0.0));
+                document
+                    .line()
+                    .start(Vector2::new(2.0, 0.0))
+                    .end(Vector2::new(4.0, 0.0));
+            })
+            .anchor(Anchor::West, Vector2::new(-4.0, 0.0))
+            .anchor(Anchor::North, Vector2::new(0.0, 1.0));
+        assert_eq!(resistor.id(), "symbol-0");
+        assert_eq!(
+            resistor.bounding_box(),
+            BoundingBox::new(Vector2::new(-4.0, -1.0), Vector2::new(4.0, 1.0))
+        );
+
+        let instance = document.instance(&resistor).at(Vector2::new(10.0, 5.0));
+        assert_eq!(instance.origin(), Vector2::new(10.0, 5.0));
+        assert_eq!(instance.west(), Vector2::new(6.0, 5.0));
+        assert_eq!(instance.northeast(), Vector2::new(14.0, 6.0));
+
+        // instances are independent of each other and follow later declarations
+        let turned = document
+            .instance(&resistor)
+            .scale(Vector2::repeat(2.0))
+            .rotation(90.0);
+        let resistor = resistor.anchor(Anchor::East, Vector2::new(4.0, 0.0));
+        assert!(close(turned.west(), Vector2::new(0.0, -8.0)));
+        assert!(close(turned.east(), Vector2::new(0.0, 8.0)));
+        assert!(close(turned.north(), Vector2::new(-2.0, 0.0)));
+        assert_eq!(instance.west(), Vector2::new(6.0, 5.0));
+        assert!(close(turned.bounding_box().min, Vector2::new(-2.0, -8.0)));
+        assert_eq!(resistor.anchors.borrow().len(), 3);
+    }
+}
@@ -0,0 +1,295 @@
+use super::{Element, ReprT};
+use crate::anchor::{anchor_rectangle, anchor_transformed, Anchor, AnchorT};
+use crate::bounding_box::{Bounded, BoundingBox};
+use crate::style::FontSize;
+use crate::svgutils::keys;
+use crate::{Scalar, Vector2};
+use std::str::FromStr;
+
+// there is no font metrics available, so the extent of a text is estimated
+// from the font size and the number of characters
+const AVERAGE_CHAR_WIDTH: Scalar = 0.6;
+const DESCENT: Scalar = 0.2;
+
+pub struct Text;
+
+impl ReprT for Text {
+    type Repr = crate::style::Font;
+}
+
+/// Horizontal alignment of the text with respect to its `at` point.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Vertical alignment of the text with respect to its `at` point.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VAlign {
+    Top,
+    Middle,
+    #[default]
+    Baseline,
+    Bottom,
+}
+
+struct Geometry {
+    origin: Vector2,
+    width: Scalar,
+    height: Scalar,
+    halign: HAlign,
+    valign: VAlign,
+}
+
+impl Element<Text> {
+    pub fn at(self, origin: Vector2) -> Self {
+        // the text is mirrored back so that it is not upside down, hence the negated Y coordinate
+        self.insert_multi([keys::X, keys::Y].into_iter().zip([origin[0], -origin[1]]));
+        self
+    }
+
+    pub fn content<S: Into<String>>(self, content: S) -> Self {
+        {
+            let mut element = self.elem.borrow_mut();
+            let children = element.get_children_mut();
+            children.clear();
+            children.push(Box::new(svg::node::Text::new(escape(&content.into()))));
+        }
+        self
+    }
+
+    pub fn halign(self, halign: HAlign) -> Self {
+        self.insert(keys::TEXT_ANCHOR, halign.as_str());
+        self
+    }
+
+    pub fn valign(self, valign: VAlign) -> Self {
+        self.insert(keys::DOMINANT_BASELINE, valign.as_str());
+        self
+    }
+
+    fn text(&self) -> String {
+        let escaped: String = self
+            .elem
+            .borrow()
+            .get_children()
+            .iter()
+            .map(|child| child.to_string())
+            .collect();
+        unescape(&escaped)
+    }
+
+    /// Estimated width and height of the rendered text.
+    pub fn extent(&self) -> Vector2 {
+        let size = self.get::<FontSize>(keys::FONT_SIZE).px();
+        let chars = self.text().chars().count() as Scalar;
+        Vector2::new(chars * size * AVERAGE_CHAR_WIDTH, size)
+    }
+
+    fn geometry(&self) -> Geometry {
+        let x = self.get(keys::X);
+        let y: Scalar = self.get(keys::Y);
+        let extent = self.extent();
+        Geometry {
+            origin: Vector2::new(x, -y),
+            width: extent[0],
+            height: extent[1],
+            halign: self.get(keys::TEXT_ANCHOR),
+            valign: self.get(keys::DOMINANT_BASELINE),
+        }
+    }
+}
+
+impl Geometry {
+    fn center(&self) -> Vector2 {
+        // positive X is right (east)
+        // positive Y is up (north)
+        let dx = match self.halign {
+            HAlign::Left => self.width / 2.0,
+            HAlign::Center => 0.0,
+            HAlign::Right => -self.width / 2.0,
+        };
+        let dy = match self.valign {
+            VAlign::Top => -self.height / 2.0,
+            VAlign::Middle => 0.0,
+            VAlign::Baseline => self.height * (0.5 - DESCENT),
+            VAlign::Bottom => self.height / 2.0,
+        };
+        self.origin + Vector2::new(dx, dy)
+    }
+}
+
+impl Bounded for Element<Text> {
+    fn bounding_box(&self) -> BoundingBox {
+        let geometry = self.geometry();
+        let half = Vector2::new(geometry.width, geometry.height) / 2.0;
+        BoundingBox::new(geometry.center() - half, geometry.center() + half)
+            .transform(&self.transformation())
+    }
+}
+
+impl AnchorT for Element<Text> {
+    fn anchor(&self, anchor: Anchor) -> Vector2 {
+        let geometry = self.geometry();
+        anchor_transformed(anchor, &self.transformation(), |anchor| {
+            anchor_rectangle(
+                anchor,
+                geometry.center(),
+                geometry.width / 2.0,
+                geometry.height / 2.0,
+            )
+        })
+    }
+}
+
+// text nodes are written as they are, so the characters with a meaning in XML are escaped
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+impl HAlign {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Left => "start",
+            Self::Center => "middle",
+            Self::Right => "end",
+        }
+    }
+}
+
+impl FromStr for HAlign {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(Self::Left),
+            "middle" => Ok(Self::Center),
+            "end" => Ok(Self::Right),
+            _ => Err(format!("invalid text anchor {}", s)),
+        }
+    }
+}
+
+impl VAlign {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Top => "text-before-edge",
+            Self::Middle => "central",
+            Self::Baseline => "alphabetic",
+            Self::Bottom => "text-after-edge",
+        }
+    }
+}
+
+impl FromStr for VAlign {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text-before-edge" => Ok(Self::Top),
+            "central" => Ok(Self::Middle),
+            "alphabetic" => Ok(Self::Baseline),
+            "text-after-edge" => Ok(Self::Bottom),
+            _ => Err(format!("invalid dominant baseline {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::assert_relative_eq;
+    use crate::style::{Font, Style};
+    use crate::svgutils::raw;
+    use std::cell::RefCell;
+    use std::ops::Deref;
+    use std::rc::Rc;
+
+    #[test]
+    fn create_and_modify() {
+        let elem = Rc::new(RefCell::new(raw::Text::new().deref().clone()));
+        let text = Element::<Text>::new(Rc::clone(&elem))
+            .content("hello")
+            .at(Vector2::new(10.0, -5.0))
+            .halign(HAlign::Center)
+            .valign(VAlign::Middle)
+            .with_style(&Style::new().font(Font::new().xl()));
+
+        assert_eq!(text.text(), "hello");
+        let geometry = text.geometry();
+        assert_eq!(geometry.origin, Vector2::new(10.0, -5.0));
+        assert_eq!(geometry.halign, HAlign::Center);
+        assert_eq!(geometry.valign, VAlign::Middle);
+        assert_eq!(geometry.height, 24.0);
+        assert_relative_eq!(text.extent(), Vector2::new(5.0 * 24.0 * 0.6, 24.0));
+
+        let text = text.content("bye");
+        assert_eq!(text.text(), "bye");
+        assert_eq!(
+            elem.borrow().get_attributes().get(keys::Y).unwrap().deref(),
+            "5"
+        );
+        assert_eq!(
+            elem.borrow()
+                .get_attributes()
+                .get(keys::TEXT_ANCHOR)
+                .unwrap()
+                .deref(),
+            "middle"
+        );
+        assert_eq!(
+            elem.borrow()
+                .get_attributes()
+                .get(keys::DOMINANT_BASELINE)
+                .unwrap()
+                .deref(),
+            "central"
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        let elem = Rc::new(RefCell::new(raw::Text::new().deref().clone()));
+        let text = Element::<Text>::new(Rc::clone(&elem)).content("R&D <1> &lt;");
+        assert_eq!(text.text(), "R&D <1> &lt;");
+        assert_eq!(text.extent()[0], 12.0 * 16.0 * AVERAGE_CHAR_WIDTH);
+        assert_eq!(
+            text.to_raw().to_string(),
+            "<text>\nR&amp;D &lt;1&gt; &amp;lt;\n</text>"
+        );
+    }
+
+    #[test]
+    fn anchors() {
+        let elem = Rc::new(RefCell::new(raw::Text::new().deref().clone()));
+        // 10 characters with the default 16px font
+        let text = Element::<Text>::new(Rc::clone(&elem))
+            .content("0123456789")
+            .at(Vector2::new(0.0, 0.0));
+        let half_width = 10.0 * 16.0 * AVERAGE_CHAR_WIDTH / 2.0;
+
+        let text = text.halign(HAlign::Center).valign(VAlign::Middle);
+        assert_relative_eq!(text.origin(), Vector2::zeros());
+        assert_relative_eq!(text.north(), Vector2::new(0.0, 8.0));
+        assert_relative_eq!(text.east(), Vector2::new(half_width, 0.0));
+
+        let text = text.halign(HAlign::Left).valign(VAlign::Top);
+        assert_relative_eq!(text.northwest(), Vector2::zeros());
+        assert_relative_eq!(text.south(), Vector2::new(half_width, -16.0));
+
+        let text = text.halign(HAlign::Right).valign(VAlign::Bottom);
+        assert_relative_eq!(text.southeast(), Vector2::zeros());
+        let bbox = text.bounding_box();
+        assert_relative_eq!(bbox.min, Vector2::new(-2.0 * half_width, 0.0));
+        assert_relative_eq!(bbox.max, Vector2::new(0.0, 16.0));
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io(std::io::Error),
+    /// The SVG document could not be converted to PDF.
+    Pdf(String),
+    /// An element is still referenced by other handles, so it cannot be moved out.
+    OutstandingHandle,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Io(err) => write!(f, "io error: {}", err),
+            Self::Pdf(err) => write!(f, "pdf conversion error: {}", err),
+            Self::OutstandingHandle => write!(f, "element is still referenced by other handles"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn display() {
+        let err = Error::from(std::io::Error::new(std::io::ErrorKind::NotFound, "missing"));
+        assert_eq!(err.to_string(), "io error: missing");
+        assert!(err.source().is_some());
+
+        let err = Error::Pdf("invalid svg".to_string());
+        assert_eq!(err.to_string(), "pdf conversion error: invalid svg");
+        assert!(err.source().is_none());
+
+        assert_eq!(
+            Error::OutstandingHandle.to_string(),
+            "element is still referenced by other handles"
+        );
+    }
+}
@@ -3,9 +3,14 @@
 #![deny(clippy::dbg_macro)]
 
 pub mod anchor;
+pub mod bounding_box;
 pub mod elements;
+mod error;
 pub mod style;
 pub mod svgutils;
+pub mod transform;
+
+pub use error::Error;
 
 // TODO move these to prelude?
 macro_rules! scalar {
@@ -62,6 +67,12 @@ macro_rules! assert_relative_eq {
     };
 }
 
+/// Whether two points agree up to the rounding errors of `Scalar` arithmetic.
+#[cfg(test)]
+pub(crate) fn close(a: Vector2, b: Vector2) -> bool {
+    (a - b).norm() < 1e-5
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -83,7 +94,6 @@ mod test {
 }
 
 // TODO
-// - text
 // - arrow
 // - a vec![] like macro that implements setter and getter functions for
 //   primitive types (like x, y, cx, cy, radius, etc)
@@ -3,6 +3,8 @@ use crate::svgutils::{
     raw::{Attributes, Value},
     ToAttributes,
 };
+use crate::Scalar;
+use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Font {
@@ -48,18 +50,13 @@ impl Font {
     }
 }
 
-impl Default for FontSize {
-    fn default() -> Self {
-        Self::M
-    }
-}
-
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum FontSize {
     XXS,
     XS,
     S,
+    #[default]
     M,
     L,
     XL,
@@ -67,6 +64,22 @@ pub enum FontSize {
     XXXL,
 }
 
+impl FontSize {
+    /// Approximate size in pixels, following the CSS absolute-size keywords.
+    pub fn px(self) -> Scalar {
+        match self {
+            Self::XXS => 9.0,
+            Self::XS => 10.0,
+            Self::S => 13.0,
+            Self::M => 16.0,
+            Self::L => 18.0,
+            Self::XL => 24.0,
+            Self::XXL => 32.0,
+            Self::XXXL => 48.0,
+        }
+    }
+}
+
 impl ToAttributes for Font {
     fn to_attributes(&self, attributes: &mut Attributes) {
         attributes.insert(keys::FONT_SIZE.into(), self.size.into());
@@ -88,6 +101,23 @@ impl From<FontSize> for Value {
     }
 }
 
+impl FromStr for FontSize {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "xx-small" => Ok(Self::XXS),
+            "x-small" => Ok(Self::XS),
+            "small" => Ok(Self::S),
+            "medium" => Ok(Self::M),
+            "large" => Ok(Self::L),
+            "x-large" => Ok(Self::XL),
+            "xx-large" => Ok(Self::XXL),
+            "xxx-large" => Ok(Self::XXXL),
+            _ => Err(format!("invalid font size {}", s)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -129,6 +159,15 @@ mod test {
         );
     }
 
+    #[test]
+    fn from_str() {
+        assert_eq!(FontSize::from_str("xx-small"), Ok(FontSize::XXS));
+        assert_eq!(FontSize::from_str("medium"), Ok(FontSize::M));
+        assert_eq!(FontSize::from_str("xxx-large"), Ok(FontSize::XXXL));
+        assert!(FontSize::from_str("huge").is_err());
+        assert_eq!(FontSize::M.px(), 16.0);
+    }
+
     #[test]
     fn write_attribute() {
         let mut attributes = Attributes::new();
@@ -3,7 +3,7 @@ mod font;
 mod stroke;
 
 pub use color::Color;
-pub use font::Font;
+pub use font::{Font, FontSize};
 pub use stroke::Stroke;
 
 use crate::svgutils::{keys, raw::Attributes, ToAttributes};
@@ -1,4 +1,5 @@
 use super::color::Color;
+use crate::elements::MarkerId;
 use crate::svgutils::{
     keys,
     raw::{Attributes, Value},
@@ -12,7 +13,7 @@ const DOT: char = '1';
 #[derive(Clone, Copy, Debug)]
 pub struct Stroke {
     color: Option<Color>,
-    markers: [Option<usize>; 3],
+    markers: [Option<MarkerId>; 3],
     opacity: u8,
     width: Scalar,
     style: StrokeStyle,
@@ -94,8 +95,8 @@ impl Stroke {
         }
     }
 
-    pub fn marker_start(mut self, marker_id: usize) -> Self {
-        self.markers[0] = Some(marker_id);
+    pub fn marker_start(mut self, marker: MarkerId) -> Self {
+        self.markers[0] = Some(marker);
         Self {
             color: self.color,
             markers: self.markers,
@@ -105,8 +106,8 @@ impl Stroke {
         }
     }
 
-    pub fn marker_mid(mut self, marker_id: usize) -> Self {
-        self.markers[1] = Some(marker_id);
+    pub fn marker_mid(mut self, marker: MarkerId) -> Self {
+        self.markers[1] = Some(marker);
         Self {
             color: self.color,
             markers: self.markers,
@@ -116,8 +117,8 @@ impl Stroke {
         }
     }
 
-    pub fn marker_end(mut self, marker_id: usize) -> Self {
-        self.markers[2] = Some(marker_id);
+    pub fn marker_end(mut self, marker: MarkerId) -> Self {
+        self.markers[2] = Some(marker);
         Self {
             color: self.color,
             markers: self.markers,
@@ -150,11 +151,12 @@ impl ToAttributes for Stroke {
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
 enum StrokeStyle {
     Dashed,
     Dashdotted,
     Dotted,
+    #[default]
     Solid,
 }
 
@@ -169,12 +171,6 @@ impl From<StrokeStyle> for Value {
     }
 }
 
-impl Default for StrokeStyle {
-    fn default() -> Self {
-        Self::Solid
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -204,9 +200,9 @@ mod test {
         assert_eq!(stroke.style, StrokeStyle::Dashdotted);
         assert_eq!(stroke.markers, [None, None, None]);
 
-        let sm = 0usize;
-        let mm = 1usize;
-        let em = 2usize;
+        let sm = MarkerId::new(0);
+        let mm = MarkerId::new(1);
+        let em = MarkerId::new(2);
         let stroke = Stroke::new()
             .dashed()
             .opacity(124)
@@ -275,7 +271,7 @@ mod test {
             format!("{} {} {} {}", DASH, DOT, DASH, DOT)
         );
 
-        let marker_id = 0usize;
+        let marker_id = MarkerId::new(0);
         let stroke = Stroke::new()
             .dashed()
             .opacity(124)
@@ -306,7 +302,7 @@ mod test {
         );
         assert_eq!(
             attributes.get(keys::MARKERS[2]).unwrap().clone().deref(),
-            format!("url(#{})", marker_id)
+            "url(#marker-0)"
         );
     }
 }
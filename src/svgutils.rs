@@ -18,6 +18,13 @@ pub mod keys {
     pub const CY: &str = "cy";
     pub const RADIUS: &str = "r";
 
+    // ellipse attributes
+    pub const RADIUS_X: &str = "rx";
+    pub const RADIUS_Y: &str = "ry";
+
+    // polygon attributes
+    pub const POINTS: &str = "points";
+
     // style
     pub const FILL: &str = "fill";
     pub const STROKE: &str = "stroke";
@@ -28,6 +35,10 @@ pub mod keys {
     // font
     pub const FONT_SIZE: &str = "font-size";
 
+    // text
+    pub const TEXT_ANCHOR: &str = "text-anchor";
+    pub const DOMINANT_BASELINE: &str = "dominant-baseline";
+
     // transform
     pub const TRANSFORM: &str = "transform";
 
@@ -47,9 +58,18 @@ pub mod keys {
     pub const MARKER_WIDTH: &str = "markerWidth";
     pub const MARKER_ORIENT: &str = "orient";
 
+    // clip
+    pub const CLIP_PATH: &str = "clip-path";
+    pub const CLIP_ID: &str = "id";
+
+    // symbol
+    pub const SYMBOL_ID: &str = "id";
+    pub const HREF: &str = "href";
+
     pub const VIEW_BOX: &str = "viewBox";
     pub const REF_X: &str = "refX";
     pub const REF_Y: &str = "refY";
+    pub const OVERFLOW: &str = "overflow";
 }
 
 pub trait ToAttributes {
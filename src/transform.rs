@@ -0,0 +1,247 @@
+use crate::{Scalar, Vector2};
+use nalgebra::{Matrix2, Matrix3};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+/// Affine transformation of the plane, mapping `x` to `linear * x + translation`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    linear: Matrix2<Scalar>,
+    translation: Vector2,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self {
+            linear: Matrix2::identity(),
+            translation: Vector2::zeros(),
+        }
+    }
+
+    pub fn translation(delta: Vector2) -> Self {
+        Self {
+            linear: Matrix2::identity(),
+            translation: delta,
+        }
+    }
+
+    /// Counterclockwise rotation around `around`, in degrees.
+    pub fn rotation(angle: Scalar, around: Vector2) -> Self {
+        let (s, c) = sin_cos(angle);
+        let linear = Matrix2::new(c, -s, s, c);
+        Self {
+            linear,
+            translation: around - linear * around,
+        }
+    }
+
+    /// Scaling along the axes, the origin of the coordinate system stays in place.
+    pub fn scaling(factors: Vector2) -> Self {
+        Self {
+            linear: Matrix2::from_diagonal(&factors),
+            translation: Vector2::zeros(),
+        }
+    }
+
+    /// Slants the Y axis by `x_angle` and the X axis by `y_angle` degrees, like `skewX` and
+    /// `skewY` in SVG.
+    pub fn skew(x_angle: Scalar, y_angle: Scalar) -> Self {
+        Self {
+            linear: Matrix2::new(
+                1.0,
+                x_angle.to_radians().tan(),
+                y_angle.to_radians().tan(),
+                1.0,
+            ),
+            translation: Vector2::zeros(),
+        }
+    }
+
+    /// Reflection across the line through `through` in direction `angle`, in degrees.
+    pub fn mirror(through: Vector2, angle: Scalar) -> Self {
+        let (s, c) = sin_cos(2.0 * angle);
+        let linear = Matrix2::new(c, s, s, -c);
+        Self {
+            linear,
+            translation: through - linear * through,
+        }
+    }
+
+    /// Applies `self` first, then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        Self {
+            linear: other.linear * self.linear,
+            translation: other.linear * self.translation + other.translation,
+        }
+    }
+
+    /// `None` if the transformation collapses the plane onto a line or a point.
+    pub fn inverse(&self) -> Option<Self> {
+        let linear = self.linear.try_inverse()?;
+        Some(Self {
+            linear,
+            translation: -(linear * self.translation),
+        })
+    }
+
+    /// Whether the transformation turns the plane over, like a mirror does.
+    pub fn is_reflection(&self) -> bool {
+        self.linear.determinant() < 0.0
+    }
+
+    /// The linear part of the transformation.
+    pub fn linear(&self) -> Matrix2<Scalar> {
+        self.linear
+    }
+
+    /// Matrix acting on homogeneous coordinates.
+    pub fn matrix(&self) -> Matrix3<Scalar> {
+        let mut matrix = self.linear.to_homogeneous();
+        matrix[(0, 2)] = self.translation[0];
+        matrix[(1, 2)] = self.translation[1];
+        matrix
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    pub fn apply(&self, point: Vector2) -> Vector2 {
+        self.linear * point + self.translation
+    }
+
+    /// Transforms a direction, which is not affected by the translation.
+    pub fn apply_vector(&self, vector: Vector2) -> Vector2 {
+        self.linear * vector
+    }
+}
+
+// sine and cosine of an angle in degrees, exact for multiples of a right angle so that axis-aligned
+// lines stay axis-aligned
+fn sin_cos(angle: Scalar) -> (Scalar, Scalar) {
+    match angle.rem_euclid(360.0) {
+        0.0 => (0.0, 1.0),
+        90.0 => (1.0, 0.0),
+        180.0 => (0.0, -1.0),
+        270.0 => (-1.0, 0.0),
+        _ => angle.to_radians().sin_cos(),
+    }
+}
+
+/// Takes the affine part of a matrix acting on homogeneous coordinates, the last row is ignored.
+impl From<Matrix3<Scalar>> for Transform {
+    fn from(matrix: Matrix3<Scalar>) -> Self {
+        Self {
+            linear: matrix.fixed_view::<2, 2>(0, 0).into(),
+            translation: Vector2::new(matrix[(0, 2)], matrix[(1, 2)]),
+        }
+    }
+}
+
+impl Display for Transform {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let m = &self.linear;
+        write!(
+            f,
+            "matrix({} {} {} {} {} {})",
+            m[(0, 0)],
+            m[(1, 0)],
+            m[(0, 1)],
+            m[(1, 1)],
+            self.translation[0],
+            self.translation[1]
+        )
+    }
+}
+
+impl FromStr for Transform {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .strip_prefix("matrix(")
+            .and_then(|arguments| arguments.strip_suffix(')'))
+            .map(|arguments| {
+                arguments
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|value| !value.is_empty())
+                    .map(Scalar::from_str)
+                    .collect::<Result<Vec<_>, _>>()
+            });
+        match values {
+            Some(Ok(values)) if values.len() == 6 => Ok(Self {
+                linear: Matrix2::new(values[0], values[2], values[1], values[3]),
+                translation: Vector2::new(values[4], values[5]),
+            }),
+            _ => Err(format!("invalid transform {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::close;
+
+    #[test]
+    fn apply() {
+        let point = Vector2::new(2.0, 1.0);
+        let rotation = Transform::rotation(90.0, Vector2::new(1.0, 1.0));
+        assert!(close(rotation.apply(point), Vector2::new(1.0, 2.0)));
+        assert!(close(rotation.apply_vector(Vector2::x()), Vector2::y()));
+
+        let scaling = Transform::scaling(Vector2::new(2.0, -1.0));
+        assert_eq!(scaling.apply(point), Vector2::new(4.0, -1.0));
+
+        let skew = Transform::skew(45.0, 0.0);
+        assert!(close(skew.apply(point), Vector2::new(3.0, 1.0)));
+
+        // the translation happens after the scaling
+        let combined = scaling.then(&Transform::translation(Vector2::new(1.0, 1.0)));
+        assert_eq!(combined.apply(point), Vector2::new(5.0, 0.0));
+        let inverse = combined.inverse().unwrap();
+        assert_eq!(inverse.apply(Vector2::new(5.0, 0.0)), point);
+        assert!(Transform::scaling(Vector2::new(0.0, 1.0))
+            .inverse()
+            .is_none());
+        assert!(Transform::default().is_identity());
+
+        let mirror = Transform::mirror(Vector2::new(0.0, 1.0), 0.0);
+        assert!(close(mirror.apply(point), Vector2::new(2.0, 1.0)));
+        assert!(close(
+            mirror.apply(Vector2::zeros()),
+            Vector2::new(0.0, 2.0)
+        ));
+        assert!(mirror.is_reflection());
+        assert!(!rotation.is_reflection());
+    }
+
+    #[test]
+    fn homogeneous() {
+        let transform = Transform::rotation(30.0, Vector2::new(1.0, 2.0))
+            .then(&Transform::translation(Vector2::new(3.0, 4.0)));
+        let matrix = transform.matrix();
+        let point = Vector2::new(-1.0, 5.0);
+        let homogeneous = matrix * point.push(1.0);
+        assert!(close(homogeneous.xy(), transform.apply(point)));
+        assert_eq!(Transform::from(matrix), transform);
+    }
+
+    #[test]
+    fn round_trip() {
+        let transform = Transform::rotation(30.0, Vector2::new(1.0, 2.0))
+            .then(&Transform::scaling(Vector2::new(2.0, 3.0)));
+        assert_eq!(transform.to_string().parse::<Transform>(), Ok(transform));
+        assert_eq!(
+            Transform::translation(Vector2::new(1.5, -2.0)).to_string(),
+            "matrix(1 0 0 1 1.5 -2)"
+        );
+        assert!("rotate(30)".parse::<Transform>().is_err());
+        assert!("matrix(1 0 0 1 0)".parse::<Transform>().is_err());
+    }
+}